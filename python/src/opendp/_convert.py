@@ -32,6 +32,9 @@ def py_to_c(value: Any, c_type, type_name: Union[RuntimeType, str] = None):
     if isinstance(value, c_type):
         return value
 
+    if c_type == CallbackFn:
+        return _wrap_py_func(value, type_name)
+
     if type_name is not None:
         RuntimeType.assert_is_similar(RuntimeType.parse(type_name), RuntimeType.infer(value))
 
@@ -81,6 +84,21 @@ def py_to_c(value: Any, c_type, type_name: Union[RuntimeType, str] = None):
     return value
 
 
+def _wrap_py_func(func, TO):
+    """Wrap the python function `func` in a CallbackFn that returns an AnyObject of type `TO`.
+    Errors raised by `func` are passed back to the library, which raises them from the measurement.
+    The CallbackFn must outlive the measurements that call it."""
+    from opendp.comb import _callback_ok, _callback_err
+
+    def wrapper_func(c_arg):
+        try:
+            return _callback_ok(py_to_c(func(c_to_py(c_arg)), c_type=AnyObjectPtr, type_name=TO))
+        except Exception as err:
+            return _callback_err(f"{type(err).__name__}: {err}")
+
+    return CallbackFn(wrapper_func)
+
+
 def c_to_py(value):
     """Map from ctypes `value` to python value.
     It is assumed that the c type is simpler than in py_to_c, as the library returns fewer types.
@@ -211,6 +229,13 @@ def _vector_to_slice(val: Sequence[Any], type_name) -> FfiSlicePtr:
         array = (ctypes.c_char_p * len(val))(*map(str_to_slice, val))
         return _wrap_in_slice(array, len(val))
 
+    if inner_type_name == "AnyMeasurementPtr":
+        if not all(isinstance(v, Measurement) for v in val):
+            raise OpenDPException("Members must be Measurements")
+        # pointers are copied as void pointers, because instances of Measurement free their contents when collected
+        array = (ctypes.c_void_p * len(val))(*(ctypes.cast(v, ctypes.c_void_p) for v in val))
+        return _wrap_in_slice(array, len(val))
+
     if inner_type_name not in ATOM_MAP:
         raise OpenDPException(f"Members must be one of {ATOM_MAP.keys()}. Found {inner_type_name}.")
 
@@ -232,6 +257,10 @@ def _slice_to_vector(raw: FfiSlicePtr, type_name: str) -> List[Any]:
         array = ctypes.cast(raw.contents.ptr, ctypes.POINTER(ctypes.c_char_p))[0:raw.contents.len]
         return list(map(lambda v: v.decode(), array))
 
+    if inner_type_name == 'AnyObject':
+        array = ctypes.cast(raw.contents.ptr, ctypes.POINTER(AnyObjectPtr))[0:raw.contents.len]
+        return list(map(c_to_py, array))
+
     return ctypes.cast(raw.contents.ptr, ctypes.POINTER(ATOM_MAP[inner_type_name]))[0:raw.contents.len]
 
 
@@ -101,6 +101,10 @@ class FfiResult(ctypes.Structure):
     ]
 
 
+# a post-processing function that takes a borrowed AnyObject, and returns a new FfiResult of an AnyObject
+CallbackFn = ctypes.CFUNCTYPE(ctypes.c_void_p, AnyObjectPtr)
+
+
 # def _str_to_c_char_p(s: Optional[str]) -> Optional[bytes]:
 #     return s and s.encode("utf-8")
 def _c_char_p_to_str(s: Optional[bytes]) -> Optional[str]:
@@ -5,12 +5,57 @@ from opendp.mod import *
 from opendp.typing import *
 
 __all__ = [
+    "_callback_ok",
+    "_callback_err",
     "make_chain_mt",
     "make_chain_tt",
+    "make_chain_pm",
     "make_basic_composition"
 ]
 
 
+def _callback_ok(
+    value: Any
+):
+    """Internal function. Wrap the `value` returned by a post-processing callback in a successful FfiResult.
+    
+    :param value: 
+    :type value: Any
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    """
+    # No type arguments to standardize.
+    # No arguments to convert to c types.
+    # Call library function.
+    function = lib.opendp_comb___callback_ok
+    function.argtypes = [AnyObjectPtr]
+    function.restype = ctypes.c_void_p
+    
+    return function(value)
+
+
+def _callback_err(
+    message: str
+):
+    """Internal function. Wrap the error `message` of a failed post-processing callback in a failed FfiResult.
+    
+    :param message: 
+    :type message: str
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    """
+    # No type arguments to standardize.
+    # Convert arguments to c types.
+    message = py_to_c(message, c_type=ctypes.c_char_p)
+    
+    # Call library function.
+    function = lib.opendp_comb___callback_err
+    function.argtypes = [ctypes.c_char_p]
+    function.restype = ctypes.c_void_p
+    
+    return function(message)
+
+
 def make_chain_mt(
     measurement: Measurement,
     transformation: Transformation
@@ -39,7 +84,9 @@ def make_chain_mt(
     function.argtypes = [Measurement, Transformation]
     function.restype = FfiResult
     
-    return c_to_py(unwrap(function(measurement, transformation), Measurement))
+    output = c_to_py(unwrap(function(measurement, transformation), Measurement))
+    output._depends_on(measurement)
+    return output
 
 
 def make_chain_tt(
@@ -73,16 +120,52 @@ def make_chain_tt(
     return c_to_py(unwrap(function(transformation1, transformation0), Transformation))
 
 
+def make_chain_pm(
+    postprocessor: Callable,
+    measurement: Measurement,
+    TO: RuntimeTypeDescriptor
+) -> Measurement:
+    """Construct the functional composition (`postprocessor` ○ `measurement`), post-processing the release of `measurement`. Returns a Measurement.
+    
+    :param postprocessor: post-processing function, applied to each release of `measurement`
+    :type postprocessor: Callable
+    :param measurement: inner privatizer
+    :type measurement: Measurement
+    :param TO: output type of `postprocessor`
+    :type TO: RuntimeTypeDescriptor
+    :return: Measurement representing the post-processed release.
+    :rtype: Measurement
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    :raises OpenDPException: packaged error from the core OpenDP library
+    """
+    assert_features("contrib")
+    
+    # Standardize type arguments.
+    TO = RuntimeType.parse(type_name=TO)
+    
+    # Convert arguments to c types.
+    postprocessor = py_to_c(postprocessor, c_type=CallbackFn, type_name=TO)
+    measurement = py_to_c(measurement, c_type=Measurement)
+    TO = py_to_c(TO, c_type=ctypes.c_char_p)
+    
+    # Call library function.
+    function = lib.opendp_comb__make_chain_pm
+    function.argtypes = [CallbackFn, Measurement, ctypes.c_char_p]
+    function.restype = FfiResult
+    
+    output = c_to_py(unwrap(function(postprocessor, measurement, TO), Measurement))
+    output._depends_on(postprocessor, measurement)
+    return output
+
+
 def make_basic_composition(
-    measurement0: Measurement,
-    measurement1: Measurement
+    measurements: List[Measurement]
 ) -> Measurement:
-    """Construct the DP composition (`measurement0`, `measurement1`). Returns a Measurement.
+    """Construct the DP composition [`measurement0`, `measurement1`, ...]. Returns a Measurement.
     
-    :param measurement0: The left member of the resulting 2-tuple.
-    :type measurement0: Measurement
-    :param measurement1: The right member of the resulting 2-tuple.
-    :type measurement1: Measurement
+    :param measurements: A vector of Measurements to compose. The resulting Measurement releases one value per member.
+    :type measurements: List[Measurement]
     :return: Measurement representing the composed transformations.
     :rtype: Measurement
     :raises AssertionError: if an argument's type differs from the expected type
@@ -93,12 +176,11 @@ def make_basic_composition(
     
     # No type arguments to standardize.
     # Convert arguments to c types.
-    measurement0 = py_to_c(measurement0, c_type=Measurement)
-    measurement1 = py_to_c(measurement1, c_type=Measurement)
+    measurements = py_to_c(measurements, c_type=AnyObjectPtr, type_name=RuntimeType(origin='Vec', args=["AnyMeasurementPtr"]))
     
     # Call library function.
     function = lib.opendp_comb__make_basic_composition
-    function.argtypes = [Measurement, Measurement]
+    function.argtypes = [AnyObjectPtr]
     function.restype = FfiResult
     
-    return c_to_py(unwrap(function(measurement0, measurement1), Measurement))
+    return c_to_py(unwrap(function(measurements), Measurement))
@@ -7,7 +7,16 @@ from opendp.typing import *
 __all__ = [
     "make_base_laplace",
     "make_base_gaussian",
+    "make_base_gaussian_zcdp",
     "make_base_geometric",
+    "make_base_discrete_gaussian",
+    "make_base_exponential_candidates",
+    "make_base_permute_and_flip",
+    "make_quantile",
+    "make_quantiles",
+    "make_randomized_response_bool",
+    "make_randomized_response",
+    "make_base_snapping",
     "make_base_stability"
 ]
 
@@ -82,6 +91,41 @@ def make_base_gaussian(
     return c_to_py(unwrap(function(scale, D), Measurement))
 
 
+def make_base_gaussian_zcdp(
+    scale,
+    D: RuntimeTypeDescriptor = "AllDomain<T>"
+) -> Measurement:
+    """Make a Measurement that adds noise from the gaussian(`scale`) distribution to the input, with a zero-concentrated DP privacy relation.
+    Adjust D to noise vector-valued data.
+    
+    :param scale: noise scale parameter to the gaussian distribution
+    :param D: Domain of the data type to be privatized. Valid values are VectorDomain<AllDomain<T>> or AllDomain<T>
+    :type D: RuntimeTypeDescriptor
+    :return: A base_gaussian_zcdp step.
+    :rtype: Measurement
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    :raises OpenDPException: packaged error from the core OpenDP library
+    """
+    assert_features("floating-point", "contrib")
+    
+    # Standardize type arguments.
+    D = RuntimeType.parse(type_name=D, generics=["T"])
+    T = get_domain_atom_or_infer(D, scale)
+    D = D.substitute(T=T)
+    
+    # Convert arguments to c types.
+    scale = py_to_c(scale, c_type=ctypes.c_void_p, type_name=T)
+    D = py_to_c(D, c_type=ctypes.c_char_p)
+    
+    # Call library function.
+    function = lib.opendp_meas__make_base_gaussian_zcdp
+    function.argtypes = [ctypes.c_void_p, ctypes.c_char_p]
+    function.restype = FfiResult
+    
+    return c_to_py(unwrap(function(scale, D), Measurement))
+
+
 def make_base_geometric(
     scale,
     bounds: Any = None,
@@ -126,6 +170,318 @@ def make_base_geometric(
     return c_to_py(unwrap(function(scale, bounds, D, QO), Measurement))
 
 
+def make_base_discrete_gaussian(
+    scale,
+    D: RuntimeTypeDescriptor = "AllDomain<i32>",
+    QO: RuntimeTypeDescriptor = None
+) -> Measurement:
+    """Make a Measurement that adds noise from the discrete gaussian(`scale`) distribution to the input, with a zero-concentrated DP privacy relation.
+    Adjust D to noise vector-valued data.
+    
+    :param scale: noise scale parameter to the discrete gaussian distribution
+    :param D: Domain of the data type to be privatized. Valid values are VectorDomain<AllDomain<T>> or AllDomain<T>
+    :type D: RuntimeTypeDescriptor
+    :param QO: Data type of the scale and budget.
+    :type QO: RuntimeTypeDescriptor
+    :return: A base_discrete_gaussian step.
+    :rtype: Measurement
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    :raises OpenDPException: packaged error from the core OpenDP library
+    """
+    assert_features("contrib")
+    
+    # Standardize type arguments.
+    D = RuntimeType.parse(type_name=D)
+    QO = RuntimeType.parse_or_infer(type_name=QO, public_example=scale)
+    
+    # Convert arguments to c types.
+    scale = py_to_c(scale, c_type=ctypes.c_void_p, type_name=QO)
+    D = py_to_c(D, c_type=ctypes.c_char_p)
+    QO = py_to_c(QO, c_type=ctypes.c_char_p)
+    
+    # Call library function.
+    function = lib.opendp_meas__make_base_discrete_gaussian
+    function.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_char_p]
+    function.restype = FfiResult
+    
+    return c_to_py(unwrap(function(scale, D, QO), Measurement))
+
+
+def make_base_exponential_candidates(
+    scale,
+    noise: str = "gumbel",
+    TIA: RuntimeTypeDescriptor = None,
+    TO: RuntimeTypeDescriptor = "i32"
+) -> Measurement:
+    """Make a Measurement that privately selects the index of the largest score in a vector of candidate scores.
+    With gumbel noise this is the exponential mechanism, and with exponential noise this is report-noisy-max.
+    
+    :param scale: noise scale parameter
+    :param noise: noise distribution. Either "gumbel" or "exponential"
+    :type noise: str
+    :param TIA: atomic data type of the scores
+    :type TIA: RuntimeTypeDescriptor
+    :param TO: data type of the released index
+    :type TO: RuntimeTypeDescriptor
+    :return: A base_exponential_candidates step.
+    :rtype: Measurement
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    :raises OpenDPException: packaged error from the core OpenDP library
+    """
+    assert_features("floating-point", "contrib")
+    
+    # Standardize type arguments.
+    TIA = RuntimeType.parse_or_infer(type_name=TIA, public_example=scale)
+    TO = RuntimeType.parse(type_name=TO)
+    
+    # Convert arguments to c types.
+    scale = py_to_c(scale, c_type=ctypes.c_void_p, type_name=TIA)
+    noise = py_to_c(noise, c_type=ctypes.c_char_p)
+    TIA = py_to_c(TIA, c_type=ctypes.c_char_p)
+    TO = py_to_c(TO, c_type=ctypes.c_char_p)
+    
+    # Call library function.
+    function = lib.opendp_meas__make_base_exponential_candidates
+    function.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_char_p, ctypes.c_char_p]
+    function.restype = FfiResult
+    
+    return c_to_py(unwrap(function(scale, noise, TIA, TO), Measurement))
+
+
+def make_base_permute_and_flip(
+    scale,
+    TIA: RuntimeTypeDescriptor = None,
+    TO: RuntimeTypeDescriptor = "i32"
+) -> Measurement:
+    """Make a Measurement that privately selects the index of the largest score in a vector of candidate scores, with the permute-and-flip mechanism.
+    
+    :param scale: noise scale parameter
+    :param TIA: atomic data type of the scores
+    :type TIA: RuntimeTypeDescriptor
+    :param TO: data type of the released index
+    :type TO: RuntimeTypeDescriptor
+    :return: A base_permute_and_flip step.
+    :rtype: Measurement
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    :raises OpenDPException: packaged error from the core OpenDP library
+    """
+    assert_features("floating-point", "contrib")
+    
+    # Standardize type arguments.
+    TIA = RuntimeType.parse_or_infer(type_name=TIA, public_example=scale)
+    TO = RuntimeType.parse(type_name=TO)
+    
+    # Convert arguments to c types.
+    scale = py_to_c(scale, c_type=ctypes.c_void_p, type_name=TIA)
+    TIA = py_to_c(TIA, c_type=ctypes.c_char_p)
+    TO = py_to_c(TO, c_type=ctypes.c_char_p)
+    
+    # Call library function.
+    function = lib.opendp_meas__make_base_permute_and_flip
+    function.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_char_p]
+    function.restype = FfiResult
+    
+    return c_to_py(unwrap(function(scale, TIA, TO), Measurement))
+
+
+def make_quantile(
+    bounds: Tuple[Any, Any],
+    alpha,
+    scale,
+    T: RuntimeTypeDescriptor = None
+) -> Measurement:
+    """Make a Measurement that privately releases the `alpha`-quantile of bounded data with the exponential mechanism.
+    The release is sampled from the intervals between the sorted data, weighted by their width and closeness in rank to the quantile.
+    
+    :param bounds: Tuple of inclusive lower and upper bounds of the data.
+    :type bounds: Tuple[Any, Any]
+    :param alpha: quantile to release, within [0, 1]. 0.5 releases the median
+    :param scale: noise scale parameter to the exponential mechanism
+    :param T: atomic data type
+    :type T: RuntimeTypeDescriptor
+    :return: A quantile step.
+    :rtype: Measurement
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    :raises OpenDPException: packaged error from the core OpenDP library
+    """
+    assert_features("floating-point", "contrib")
+    
+    # Standardize type arguments.
+    T = RuntimeType.parse_or_infer(type_name=T, public_example=get_first(bounds))
+    
+    # Convert arguments to c types.
+    bounds = py_to_c(bounds, c_type=AnyObjectPtr, type_name=RuntimeType(origin='Tuple', args=[T, T]))
+    alpha = py_to_c(alpha, c_type=ctypes.c_void_p, type_name=T)
+    scale = py_to_c(scale, c_type=ctypes.c_void_p, type_name=T)
+    T = py_to_c(T, c_type=ctypes.c_char_p)
+    
+    # Call library function.
+    function = lib.opendp_meas__make_quantile
+    function.argtypes = [AnyObjectPtr, ctypes.c_void_p, ctypes.c_void_p, ctypes.c_char_p]
+    function.restype = FfiResult
+    
+    return c_to_py(unwrap(function(bounds, alpha, scale, T), Measurement))
+
+
+def make_quantiles(
+    bounds: Tuple[Any, Any],
+    alphas: Any,
+    scale,
+    T: RuntimeTypeDescriptor = None
+) -> Measurement:
+    """Make a Measurement that privately releases many quantiles of bounded data at once.
+    The middle quantile is released with the exponential mechanism, and the remaining quantiles are released recursively on the data split at the release.
+    
+    :param bounds: Tuple of inclusive lower and upper bounds of the data.
+    :type bounds: Tuple[Any, Any]
+    :param alphas: strictly increasing quantiles to release, each within [0, 1]
+    :type alphas: Any
+    :param scale: noise scale parameter to the exponential mechanism at each level of the recursion
+    :param T: atomic data type
+    :type T: RuntimeTypeDescriptor
+    :return: A quantiles step.
+    :rtype: Measurement
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    :raises OpenDPException: packaged error from the core OpenDP library
+    """
+    assert_features("floating-point", "contrib")
+    
+    # Standardize type arguments.
+    T = RuntimeType.parse_or_infer(type_name=T, public_example=get_first(bounds))
+    
+    # Convert arguments to c types.
+    bounds = py_to_c(bounds, c_type=AnyObjectPtr, type_name=RuntimeType(origin='Tuple', args=[T, T]))
+    alphas = py_to_c(alphas, c_type=AnyObjectPtr, type_name=RuntimeType(origin='Vec', args=[T]))
+    scale = py_to_c(scale, c_type=ctypes.c_void_p, type_name=T)
+    T = py_to_c(T, c_type=ctypes.c_char_p)
+    
+    # Call library function.
+    function = lib.opendp_meas__make_quantiles
+    function.argtypes = [AnyObjectPtr, AnyObjectPtr, ctypes.c_void_p, ctypes.c_char_p]
+    function.restype = FfiResult
+    
+    return c_to_py(unwrap(function(bounds, alphas, scale, T), Measurement))
+
+
+def make_randomized_response_bool(
+    prob,
+    constant_time: bool = False,
+    Q: RuntimeTypeDescriptor = None
+) -> Measurement:
+    """Make a Measurement that releases a boolean, answering truthfully with probability `prob` and flipping it otherwise.
+    
+    :param prob: probability of returning the true answer. Must be within [0.5, 1)
+    :param constant_time: set to true to sample the coin flip in constant time
+    :type constant_time: bool
+    :param Q: data type of the probability and of epsilon
+    :type Q: RuntimeTypeDescriptor
+    :return: A randomized_response_bool step.
+    :rtype: Measurement
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    :raises OpenDPException: packaged error from the core OpenDP library
+    """
+    assert_features("contrib")
+    
+    # Standardize type arguments.
+    Q = RuntimeType.parse_or_infer(type_name=Q, public_example=prob)
+    
+    # Convert arguments to c types.
+    prob = py_to_c(prob, c_type=ctypes.c_void_p, type_name=Q)
+    constant_time = py_to_c(constant_time, c_type=ctypes.c_bool)
+    Q = py_to_c(Q, c_type=ctypes.c_char_p)
+    
+    # Call library function.
+    function = lib.opendp_meas__make_randomized_response_bool
+    function.argtypes = [ctypes.c_void_p, ctypes.c_bool, ctypes.c_char_p]
+    function.restype = FfiResult
+    
+    return c_to_py(unwrap(function(prob, constant_time, Q), Measurement))
+
+
+def make_randomized_response(
+    categories: Any,
+    prob,
+    constant_time: bool = False,
+    T: RuntimeTypeDescriptor = None,
+    Q: RuntimeTypeDescriptor = None
+) -> Measurement:
+    """Make a Measurement that releases a category, answering truthfully with probability `prob`,
+    and otherwise releasing one of the other `categories` uniformly at random.
+    
+    :param categories: set of categories that may be released
+    :type categories: Any
+    :param prob: probability of returning the true answer. Must be within [1/k, 1), where k is the number of categories
+    :param constant_time: set to true to sample the coin flip in constant time
+    :type constant_time: bool
+    :param T: categorical/hashable data type of the categories
+    :type T: RuntimeTypeDescriptor
+    :param Q: data type of the probability and of epsilon
+    :type Q: RuntimeTypeDescriptor
+    :return: A randomized_response step.
+    :rtype: Measurement
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    :raises OpenDPException: packaged error from the core OpenDP library
+    """
+    assert_features("contrib")
+    
+    # Standardize type arguments.
+    T = RuntimeType.parse_or_infer(type_name=T, public_example=next(iter(categories), None))
+    Q = RuntimeType.parse_or_infer(type_name=Q, public_example=prob)
+    
+    # Convert arguments to c types.
+    categories = py_to_c(categories, c_type=AnyObjectPtr, type_name=RuntimeType(origin='Vec', args=[T]))
+    prob = py_to_c(prob, c_type=ctypes.c_void_p, type_name=Q)
+    constant_time = py_to_c(constant_time, c_type=ctypes.c_bool)
+    T = py_to_c(T, c_type=ctypes.c_char_p)
+    Q = py_to_c(Q, c_type=ctypes.c_char_p)
+    
+    # Call library function.
+    function = lib.opendp_meas__make_randomized_response
+    function.argtypes = [AnyObjectPtr, ctypes.c_void_p, ctypes.c_bool, ctypes.c_char_p, ctypes.c_char_p]
+    function.restype = FfiResult
+    
+    return c_to_py(unwrap(function(categories, prob, constant_time, T, Q), Measurement))
+
+
+def make_base_snapping(
+    scale: float,
+    bound: float
+) -> Measurement:
+    """Make a Measurement that releases a bounded scalar with the snapping mechanism.
+    The input is clamped, laplace noise is added, and the result is rounded to a power-of-two grid and clamped.
+    
+    :param scale: noise scale parameter to the laplace distribution
+    :type scale: float
+    :param bound: inputs and outputs are clamped to [-bound, bound]
+    :type bound: float
+    :return: A base_snapping step.
+    :rtype: Measurement
+    :raises AssertionError: if an argument's type differs from the expected type
+    :raises UnknownTypeError: if a type-argument fails to parse
+    :raises OpenDPException: packaged error from the core OpenDP library
+    """
+    assert_features("floating-point", "contrib")
+    
+    # No type arguments to standardize.
+    # Convert arguments to c types.
+    scale = py_to_c(scale, c_type=ctypes.c_double)
+    bound = py_to_c(bound, c_type=ctypes.c_double)
+    
+    # Call library function.
+    function = lib.opendp_meas__make_base_snapping
+    function.argtypes = [ctypes.c_double, ctypes.c_double]
+    function.restype = FfiResult
+    
+    return c_to_py(unwrap(function(scale, bound), Measurement))
+
+
 def make_base_stability(
     size: int,
     scale,
@@ -110,6 +110,10 @@ class Measurement(ctypes.POINTER(AnyMeasurement)):
         from opendp.typing import RuntimeType
         return RuntimeType.parse(measurement_input_carrier_type(self))
 
+    def _depends_on(self, *args):
+        """Extends the memory lifetime of args to match the lifetime of this measurement."""
+        self._dependencies = getattr(self, "_dependencies", []) + list(args)
+
     def __del__(self):
         from opendp.core import _measurement_free
         _measurement_free(self)
@@ -3,7 +3,7 @@ import typing
 from collections.abc import Hashable
 from typing import Union, Any, Type, List
 
-from opendp.mod import UnknownTypeException
+from opendp.mod import UnknownTypeException, Measurement
 from opendp._lib import ATOM_EQUIVALENCE_CLASSES
 
 if sys.version_info >= (3, 7):
@@ -107,9 +107,11 @@ class RuntimeType(object):
             closeness = {
                 'SubstituteDistance': SubstituteDistance,
                 'SymmetricDistance': SymmetricDistance,
+                'DiscreteDistance': DiscreteDistance,
                 'AbsoluteDistance': AbsoluteDistance,
                 'L1Distance': L1Distance,
                 'L2Distance': L2Distance,
+                'LInfDistance': LInfDistance,
                 'MaxDivergence': MaxDivergence,
                 'SmoothedMaxDivergence': SmoothedMaxDivergence
             }.get(origin)
@@ -182,6 +184,9 @@ class RuntimeType(object):
                 cls.infer(next(iter(public_example.values())))
             ])
 
+        if isinstance(public_example, Measurement):
+            return "AnyMeasurementPtr"
+
         if public_example is None:
             return RuntimeType('Option', [UnknownType("Constructed Option from a None variant")])
 
@@ -283,6 +288,7 @@ class DatasetMetric(RuntimeType):
 
 SubstituteDistance = DatasetMetric('SubstituteDistance')
 SymmetricDistance = DatasetMetric('SymmetricDistance')
+DiscreteDistance = DatasetMetric('DiscreteDistance')
 
 
 class SensitivityMetric(RuntimeType):
@@ -296,6 +302,7 @@ class SensitivityMetric(RuntimeType):
 AbsoluteDistance = SensitivityMetric('AbsoluteDistance')
 L1Distance = SensitivityMetric('L1Distance')
 L2Distance = SensitivityMetric('L2Distance')
+LInfDistance = SensitivityMetric('LInfDistance')
 
 
 class PrivacyMeasure(RuntimeType):
@@ -81,3 +81,39 @@ def test_bisect_chain():
 
     scale = binary_search_param(lambda s: pre >> make_base_laplace(scale=s), d_in=1, d_out=1.)
     assert scale - 0.1 < 1e-8
+
+
+def test_chain_pm():
+    from opendp.trans import make_count
+    from opendp.meas import make_base_geometric
+    from opendp.comb import make_chain_pm
+
+    count = make_count(TIA=int, TO=int) >> make_base_geometric(scale=0.5)
+    chain = make_chain_pm(lambda v: max(v, 0), count, TO=int)
+    assert chain([1, 2, 3]) >= 0
+    assert chain.check(1, 2.)
+    assert not chain.check(1, 1.)
+
+    # the output type of the postprocessor must be given
+    with pytest.raises(TypeError):
+        make_chain_pm(lambda v: max(v, 0), count)
+
+
+def test_chain_pm_error():
+    from opendp.trans import make_count
+    from opendp.meas import make_base_geometric
+    from opendp.comb import make_chain_pm
+    from opendp.mod import OpenDPException
+
+    def fail(_v):
+        raise ValueError("oops")
+
+    chain = make_chain_pm(fail, make_base_geometric(scale=0.5), TO=int)
+    with pytest.raises(OpenDPException, match="ValueError: oops"):
+        chain(1)
+
+    # the postprocessor lives as long as any measurement built from it
+    import gc
+    chain = make_count(TIA=int, TO=int) >> make_chain_pm(lambda v: v * 2, make_base_geometric(scale=0.5), TO=int)
+    gc.collect()
+    assert chain([1, 2, 3]) % 2 == 0
@@ -58,6 +58,83 @@ def test_base_vector_geometric():
     assert not meas.check(1, 0.49999)
 
 
+def test_base_discrete_gaussian():
+    from opendp.meas import make_base_discrete_gaussian
+    meas = make_base_discrete_gaussian(scale=2.)
+    print("base_discrete_gaussian:", meas(100))
+    assert meas.check(1, 0.125)
+    assert not meas.check(1, 0.124)
+
+    meas = make_base_discrete_gaussian(scale=2., D="VectorDomain<AllDomain<i32>>")
+    print("vector base_discrete_gaussian:", meas([100, 10, 12]))
+    assert meas.check(1, 0.125)
+
+
+def test_base_snapping():
+    from opendp.meas import make_base_snapping
+    meas = make_base_snapping(scale=1.5, bound=100.)
+    release = meas(1000.)
+    print("base_snapping:", release)
+    assert -100. <= release <= 100.
+    assert meas.check(1., 0.6667)
+    assert not meas.check(1., 0.6666)
+
+
+def test_base_exponential_candidates():
+    from opendp.meas import make_base_exponential_candidates
+    for noise in ["gumbel", "exponential"]:
+        meas = make_base_exponential_candidates(scale=0.1, noise=noise)
+        assert meas([1., 100., 2.]) == 1
+        assert meas.check(1., 20.)
+        assert not meas.check(1., 19.9)
+
+
+def test_base_permute_and_flip():
+    from opendp.meas import make_base_permute_and_flip
+    meas = make_base_permute_and_flip(scale=0.1)
+    assert meas([1., 100., 2.]) == 1
+    assert meas.check(1., 20.)
+    assert not meas.check(1., 19.9)
+
+
+def test_quantile():
+    from opendp.trans import make_clamp
+    from opendp.meas import make_quantile
+    meas = make_clamp(bounds=(0., 10.)) >> make_quantile(bounds=(0., 10.), alpha=0.5, scale=1.)
+    release = meas([1., 2., 3., 4., 5., 20.])
+    print("quantile:", release)
+    assert 0. <= release <= 10.
+    assert meas.check(1, 2.)
+    assert not meas.check(1, 1.999)
+
+
+def test_quantiles():
+    from opendp.trans import make_clamp
+    from opendp.meas import make_quantiles
+    meas = make_clamp(bounds=(0., 10.)) >> make_quantiles(bounds=(0., 10.), alphas=[0.25, 0.5, 0.75], scale=1.)
+    release = meas([1., 2., 3., 4., 5., 20.])
+    print("quantiles:", release)
+    assert len(release) == 3
+    assert meas.check(1, 4.)
+    assert not meas.check(1, 3.999)
+
+
+def test_randomized_response_bool():
+    from opendp.meas import make_randomized_response_bool
+    meas = make_randomized_response_bool(prob=0.75)
+    assert meas(True) in [True, False]
+    assert meas.check(1, 1.0987)
+    assert not meas.check(1, 1.0986)
+
+
+def test_randomized_response():
+    from opendp.meas import make_randomized_response
+    meas = make_randomized_response(categories=["A", "B", "C", "D"], prob=0.75)
+    assert meas("A") in ["A", "B", "C", "D"]
+    assert meas.check(1, 2.1973)
+    assert not meas.check(1, 2.1972)
+
+
 def test_base_stability():
     from opendp.trans import make_count_by
     from opendp.meas import make_base_stability
@@ -280,6 +280,8 @@ fn generate_public_example(func: &Function, type_arg: &Argument) -> Option<Strin
 
     // code generation
     args.iter()
+        // the output type of a callback can't be inferred from the callback itself
+        .filter(|arg| arg.c_type.as_deref() != Some("CallbackFn"))
         .filter_map(|arg| match &arg.rust_type {
             Some(RuntimeType::Name(name)) => (name == type_name).then(|| arg.name()),
             Some(RuntimeType::Raise { origin, args }) =>
@@ -408,17 +410,31 @@ fn generate_call(
                        call = call, restype = func.ret.python_unwrapped_ctype(typemap))
     }
     if !func.ret.do_not_convert { call = format!(r#"c_to_py({})"#, call) }
+
+    // a measurement may call back into python objects that it does not own, so they must live as long as it does
+    let dependencies = func.args.iter()
+        .filter(|arg| !arg.is_type && matches!(arg.c_type().as_str(), "CallbackFn" | "const AnyMeasurement *"))
+        .map(|arg| arg.name())
+        .collect::<Vec<_>>();
+    let ret = if func.ret.c_type() == "FfiResult<AnyMeasurement *>" && !dependencies.is_empty() {
+        format!(r#"output = {call}
+output._depends_on({dependencies})
+return output"#, call = call, dependencies = dependencies.join(", "))
+    } else {
+        format!("return {}", call)
+    };
+
     format!(r#"# Call library function.
 function = lib.opendp_{module_name}__{func_name}
 function.argtypes = [{ctype_args}]
 function.restype = {ctype_restype}
 
-return {call}"#,
+{ret}"#,
             module_name = module_name,
             func_name = func_name,
             ctype_args = func.args.iter()
                 .map(|v| v.python_origin_ctype(typemap))
                 .collect::<Vec<_>>().join(", "),
             ctype_restype = ctype_restype,
-            call = call)
+            ret = ret)
 }
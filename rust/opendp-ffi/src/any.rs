@@ -282,6 +282,7 @@ impl PartialOrd for AnyMetricDistance {
 #[derive(Clone, PartialEq)]
 pub struct AnyMeasure {
     pub measure: AnyBoxClonePartialEqDebug,
+    pub measure_type: Type,
     pub distance_type: Type
 }
 
@@ -289,6 +290,7 @@ impl AnyMeasure {
     pub fn new<M: 'static + Measure>(measure: M) -> Self {
         Self {
             measure: AnyBoxClonePartialEqDebug::new_clone_partial_eq_debug(measure),
+            measure_type: Type::of::<M>(),
             distance_type: Type::of::<M::Distance>()
         }
     }
@@ -400,16 +402,17 @@ fn make_any_relation<QI: 'static, QO: 'static, AQI: Downcast, AQO: Downcast>(rel
     }
 }
 
-fn make_any_map<QI, QO, AQI>(map: &Option<Rc<dyn Fn(&QI) -> Fallible<Box<QO>>>>) -> Option<impl Fn(&AQI) -> Fallible<Box<AnyMetricDistance>>>
+fn make_any_map<QI, QO, AQI, AQO>(map: &Option<Rc<dyn Fn(&QI) -> Fallible<Box<QO>>>>, new_any: fn(QO) -> AQO) -> Option<impl Fn(&AQI) -> Fallible<Box<AQO>>>
     where QI: 'static + PartialOrd,
           QO: 'static + PartialOrd + Clone,
-          AQI: Downcast {
+          AQI: Downcast,
+          AQO: 'static {
     map.as_ref().map(|map| {
         let map = map.clone();
-        move |d_in: &AQI| -> Fallible<Box<AnyMetricDistance>> {
+        move |d_in: &AQI| -> Fallible<Box<AQO>> {
             let d_in = d_in.downcast_ref()?;
             let d_out = map(d_in);
-            d_out.map(|d| AnyMetricDistance::new(*d)).map(Box::new)
+            d_out.map(|d| new_any(*d)).map(Box::new)
         }
     })
 }
@@ -421,12 +424,13 @@ pub trait IntoAnyPrivacyRelationExt {
 }
 
 impl<MI: Metric, MO: Measure> IntoAnyPrivacyRelationExt for PrivacyRelation<MI, MO>
-    where MI::Distance: 'static + Clone + PartialOrd,
-          MO::Distance: 'static + Clone + PartialOrd {
+    where MI::Distance: 'static + Clone + MetricDistance,
+          MO::Distance: 'static + Clone + MeasureDistance {
     fn into_any(self) -> AnyPrivacyRelation {
         AnyPrivacyRelation::new_all(
             make_any_relation(&self.relation),
-            make_any_map(&self.backward_map),
+            make_any_map(&self.forward_map, AnyMeasureDistance::new),
+            make_any_map(&self.backward_map, AnyMetricDistance::new),
         )
     }
 }
@@ -443,8 +447,8 @@ impl<MI: Metric, MO: Metric> IntoAnyStabilityRelationExt for StabilityRelation<M
     fn into_any(self) -> AnyStabilityRelation {
         AnyStabilityRelation::new_all(
             make_any_relation(&self.relation),
-            make_any_map(&self.forward_map),
-            make_any_map(&self.backward_map),
+            make_any_map(&self.forward_map, AnyMetricDistance::new),
+            make_any_map(&self.backward_map, AnyMetricDistance::new),
         )
     }
 }
@@ -453,6 +457,9 @@ impl<MI: Metric, MO: Metric> IntoAnyStabilityRelationExt for StabilityRelation<M
 /// passed back and forth over FFI.
 pub type AnyMeasurement = Measurement<AnyDomain, AnyDomain, AnyMetric, AnyMeasure>;
 
+/// A pointer to an AnyMeasurement, for passing collections of Measurements over FFI.
+pub type AnyMeasurementPtr = *const AnyMeasurement;
+
 /// A trait for turning a Measurement into an AnyMeasurement. We can't used From because it'd conflict
 /// with blanket implementation, and we need an extension trait to add methods to Measurement.
 pub trait IntoAnyMeasurementExt {
@@ -462,8 +469,8 @@ pub trait IntoAnyMeasurementExt {
 impl<DI: 'static + Domain, DO: 'static + Domain, MI: 'static + Metric, MO: 'static + Measure> IntoAnyMeasurementExt for Measurement<DI, DO, MI, MO>
     where DI::Carrier: 'static,
           DO::Carrier: 'static,
-          MI::Distance: 'static + Clone + PartialOrd,
-          MO::Distance: 'static + Clone + PartialOrd {
+          MI::Distance: 'static + Clone + MetricDistance,
+          MO::Distance: 'static + Clone + MeasureDistance {
     fn into_any(self) -> AnyMeasurement {
         AnyMeasurement::new(
             AnyDomain::new(self.input_domain),
@@ -491,7 +498,7 @@ impl<DO: 'static + Domain> IntoAnyMeasurementOutExt for Measurement<AnyDomain, D
             self.function.into_any_out(),
             self.input_metric,
             self.output_measure,
-            self.privacy_relation.into_any(),
+            self.privacy_relation,
         )
     }
 }
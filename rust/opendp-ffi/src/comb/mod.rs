@@ -1,7 +1,68 @@
-use opendp::comb::{make_basic_composition, make_chain_mt, make_chain_tt};
+use std::convert::TryFrom;
+use std::os::raw::c_char;
 
-use crate::any::{AnyMeasurement, AnyTransformation, IntoAnyMeasurementOutExt};
+use opendp::{err, fallible};
+use opendp::comb::{make_basic_composition, make_chain_mt, make_chain_tt, BasicCompositionMeasure};
+use opendp::core::Function;
+use opendp::dist::{MaxDivergence, SmoothedMaxDivergence, ZeroConcentratedDivergence};
+use opendp::error::Fallible;
+use opendp::traits::MeasureDistance;
+
+use opendp::poly::PolyDomain;
+
+use crate::any::{AnyDomain, AnyMeasure, AnyMeasureDistance, AnyMeasurement, AnyMeasurementPtr, AnyObject, AnyTransformation, Downcast, IntoAnyMeasurementOutExt};
 use crate::core::FfiResult;
+use crate::util;
+use crate::util::Type;
+
+impl BasicCompositionMeasure for AnyMeasure {
+    fn compose(&self, d_i: Vec<AnyMeasureDistance>) -> Fallible<AnyMeasureDistance> {
+        fn monomorphize<M: 'static + BasicCompositionMeasure>(self_: &AnyMeasure, d_i: Vec<AnyMeasureDistance>) -> Fallible<AnyMeasureDistance>
+            where M::Distance: 'static + Clone + MeasureDistance {
+            let d_i = d_i.into_iter().map(|d| d.downcast::<M::Distance>()).collect::<Fallible<_>>()?;
+            self_.downcast_ref::<M>()?.compose(d_i).map(AnyMeasureDistance::new)
+        }
+        dispatch!(monomorphize, [
+            (self.measure_type, [MaxDivergence<f32>, MaxDivergence<f64>, SmoothedMaxDivergence<f32>, SmoothedMaxDivergence<f64>, ZeroConcentratedDivergence<f32>, ZeroConcentratedDivergence<f64>])
+        ], (self, d_i))
+    }
+
+    fn split(&self, d_out: &AnyMeasureDistance, d_mid: Vec<Option<AnyMeasureDistance>>) -> Fallible<Option<Vec<AnyMeasureDistance>>> {
+        fn monomorphize<M: 'static + BasicCompositionMeasure>(self_: &AnyMeasure, d_out: &AnyMeasureDistance, d_mid: Vec<Option<AnyMeasureDistance>>) -> Fallible<Option<Vec<AnyMeasureDistance>>>
+            where M::Distance: 'static + Clone + MeasureDistance {
+            let d_out = d_out.downcast_ref::<M::Distance>()?;
+            let d_mid = d_mid.into_iter()
+                .map(|d| d.map(|d| d.downcast::<M::Distance>()).transpose())
+                .collect::<Fallible<_>>()?;
+            Ok(self_.downcast_ref::<M>()?.split(d_out, d_mid)?
+                .map(|d_outs| d_outs.into_iter().map(AnyMeasureDistance::new).collect()))
+        }
+        dispatch!(monomorphize, [
+            (self.measure_type, [MaxDivergence<f32>, MaxDivergence<f64>, SmoothedMaxDivergence<f32>, SmoothedMaxDivergence<f64>, ZeroConcentratedDivergence<f32>, ZeroConcentratedDivergence<f64>])
+        ], (self, d_out, d_mid))
+    }
+
+    fn is_within(&self, d: &AnyMeasureDistance, d_bound: &AnyMeasureDistance) -> Fallible<bool> {
+        fn monomorphize<M: 'static + BasicCompositionMeasure>(self_: &AnyMeasure, d: &AnyMeasureDistance, d_bound: &AnyMeasureDistance) -> Fallible<bool>
+            where M::Distance: 'static + Clone + MeasureDistance {
+            self_.downcast_ref::<M>()?.is_within(d.downcast_ref::<M::Distance>()?, d_bound.downcast_ref::<M::Distance>()?)
+        }
+        dispatch!(monomorphize, [
+            (self.measure_type, [MaxDivergence<f32>, MaxDivergence<f64>, SmoothedMaxDivergence<f32>, SmoothedMaxDivergence<f64>, ZeroConcentratedDivergence<f32>, ZeroConcentratedDivergence<f64>])
+        ], (self, d, d_bound))
+    }
+
+    fn max(&self, d_i: Vec<AnyMeasureDistance>) -> Fallible<AnyMeasureDistance> {
+        fn monomorphize<M: 'static + BasicCompositionMeasure>(self_: &AnyMeasure, d_i: Vec<AnyMeasureDistance>) -> Fallible<AnyMeasureDistance>
+            where M::Distance: 'static + Clone + MeasureDistance {
+            let d_i = d_i.into_iter().map(|d| d.downcast::<M::Distance>()).collect::<Fallible<_>>()?;
+            self_.downcast_ref::<M>()?.max(d_i).map(AnyMeasureDistance::new)
+        }
+        dispatch!(monomorphize, [
+            (self.measure_type, [MaxDivergence<f32>, MaxDivergence<f64>, SmoothedMaxDivergence<f32>, SmoothedMaxDivergence<f64>, ZeroConcentratedDivergence<f32>, ZeroConcentratedDivergence<f64>])
+        ], (self, d_i))
+    }
+}
 
 #[no_mangle]
 pub extern "C" fn opendp_comb__make_chain_mt(measurement1: *const AnyMeasurement, transformation0: *const AnyTransformation) -> FfiResult<*mut AnyMeasurement> {
@@ -17,29 +78,84 @@ pub extern "C" fn opendp_comb__make_chain_tt(transformation1: *const AnyTransfor
     make_chain_tt(transformation1, transformation0, None).into()
 }
 
+/// A function implemented by the caller, for use in post-processing.
+/// It is given a borrowed AnyObject, and must return a result made by `opendp_comb___callback_ok` or `opendp_comb___callback_err`.
+pub type CallbackFn = extern "C" fn(*const AnyObject) -> *mut FfiResult<*mut AnyObject>;
+
 #[no_mangle]
-pub extern "C" fn opendp_comb__make_basic_composition(measurement0: *const AnyMeasurement, measurement1: *const AnyMeasurement) -> FfiResult<*mut AnyMeasurement> {
-    let measurement0 = try_as_ref!(measurement0);
-    let measurement1 = try_as_ref!(measurement1);
+pub extern "C" fn opendp_comb___callback_ok(value: *mut AnyObject) -> *mut FfiResult<*mut AnyObject> {
+    util::into_raw(FfiResult::Ok(value))
+}
+
+#[no_mangle]
+pub extern "C" fn opendp_comb___callback_err(message: *const c_char) -> *mut FfiResult<*mut AnyObject> {
+    let message = util::to_str(message).unwrap_or("post-processing callback failed");
+    util::into_raw(FfiResult::from(err!(FailedFunction, "{}", message)))
+}
+
+#[no_mangle]
+pub extern "C" fn opendp_comb__make_chain_pm(postprocessor: CallbackFn, measurement: *const AnyMeasurement, TO: *const c_char) -> FfiResult<*mut AnyMeasurement> {
+    let measurement = try_as_ref!(measurement);
+    let TO = try_!(Type::try_from(TO));
+    let function = Function::new_fallible(move |arg: &AnyObject| -> Fallible<AnyObject> {
+        let res = match util::into_owned(postprocessor(arg as *const AnyObject))? {
+            FfiResult::Ok(res) => util::into_owned(res)?,
+            FfiResult::Err(err) => {
+                let err = util::into_owned(err)?;
+                return fallible!(FailedFunction, "{}", util::to_option_str(err.message)?.unwrap_or("post-processing callback failed"))
+            }
+        };
+        if res.type_.id != TO.id {
+            return fallible!(FailedFunction, "expected post-processing callback to return {}, got {}", TO.descriptor, res.type_.descriptor)
+        }
+        Ok(res)
+    });
+    // The output type of the callback is only known at runtime, so the output domain is erased to a PolyDomain,
+    // as in make_basic_composition. The core make_chain_pm would need a Default output domain.
+    FfiResult::Ok(util::into_raw(AnyMeasurement::new(
+        measurement.input_domain.clone(),
+        AnyDomain::new(PolyDomain::new()),
+        Function::make_chain(&function, &measurement.function),
+        measurement.input_metric.clone(),
+        measurement.output_measure.clone(),
+        measurement.privacy_relation.clone())))
+}
+
+#[no_mangle]
+pub extern "C" fn opendp_comb__make_basic_composition(measurements: *const AnyObject) -> FfiResult<*mut AnyMeasurement> {
+    let measurements: &Vec<AnyMeasurementPtr> = try_!(try_as_ref!(measurements).downcast_ref());
+    // The members may release values of different types, so their output domains are erased to a
+    // common PolyDomain. The released values are still AnyObjects of the original types.
+    let measurements = try_!(measurements.iter()
+        .map(|ptr| util::as_ref(*ptr)
+            .ok_or_else(|| err!(FFI, "Attempted to follow a null pointer to a measurement"))
+            .map(|m| AnyMeasurement::new(
+                m.input_domain.clone(),
+                AnyDomain::new(PolyDomain::new()),
+                m.function.clone(),
+                m.input_metric.clone(),
+                m.output_measure.clone(),
+                m.privacy_relation.clone())))
+        .collect::<Fallible<Vec<_>>>());
     // This one has a different pattern than most constructors. The result of make_basic_composition()
-    // will be Measurement<AnyDomain, PairDomain, AnyMetric, AnyMeasure>. We need to get back to
+    // will be Measurement<AnyDomain, VectorDomain, AnyMetric, AnyMeasure>. We need to get back to
     // AnyMeasurement, but using IntoAnyMeasurementExt::into_any() would double-wrap the input.
     // That's what IntoAnyMeasurementOutExt::into_any_out() is for.
-    make_basic_composition(measurement0, measurement1).map(IntoAnyMeasurementOutExt::into_any_out).into()
+    make_basic_composition(measurements.iter().collect()).map(IntoAnyMeasurementOutExt::into_any_out).into()
 }
 
-
 #[cfg(test)]
 mod tests {
     use opendp::core::{Function, Measurement, PrivacyRelation, Transformation};
     use opendp::dist::{MaxDivergence, SymmetricDistance};
     use opendp::dom::AllDomain;
     use opendp::error::*;
+    use opendp::meas;
     use opendp::trans;
 
-    use crate::any::{AnyObject, Downcast, IntoAnyMeasurementExt, IntoAnyTransformationExt};
+    use crate::any::{AnyMetricDistance, IntoAnyMeasurementExt, IntoAnyTransformationExt};
     use crate::core;
-    use crate::util;
+    use crate::util::ToCharP;
 
     use super::*;
     use opendp::traits::CheckNull;
@@ -85,16 +201,59 @@ mod tests {
         Ok(())
     }
 
+    extern "C" fn add_one(arg: *const AnyObject) -> *mut FfiResult<*mut AnyObject> {
+        let arg: &i32 = util::as_ref(arg).unwrap_test().downcast_ref().unwrap_test();
+        opendp_comb___callback_ok(AnyObject::new_raw(arg + 1))
+    }
+
+    extern "C" fn fail(_arg: *const AnyObject) -> *mut FfiResult<*mut AnyObject> {
+        opendp_comb___callback_err("ValueError: oops".to_char_p())
+    }
+
+    #[test]
+    fn test_make_chain_pm() -> Fallible<()> {
+        let measurement = util::into_raw(make_test_measurement::<i32>().into_any());
+        let chain = Result::from(opendp_comb__make_chain_pm(add_one, measurement, "i32".to_char_p()))?;
+        let arg = AnyObject::new_raw(999);
+        let res = core::opendp_core__measurement_invoke(&chain, arg);
+        let res: i32 = Fallible::from(res)?.downcast()?;
+        assert_eq!(res, 1000);
+
+        // the callback must return the declared type
+        let chain = Result::from(opendp_comb__make_chain_pm(add_one, measurement, "f64".to_char_p()))?;
+        let res = core::opendp_core__measurement_invoke(&chain, AnyObject::new_raw(999));
+        assert!(Fallible::from(res).is_err());
+
+        // errors raised in the callback are passed through
+        let chain = Result::from(opendp_comb__make_chain_pm(fail, measurement, "i32".to_char_p()))?;
+        let res = core::opendp_core__measurement_invoke(&chain, AnyObject::new_raw(999));
+        assert_eq!(Fallible::from(res).unwrap_err().message, Some("ValueError: oops".to_string()));
+        Ok(())
+    }
+
     #[test]
     fn test_make_basic_composition() -> Fallible<()> {
-        let measurement0 = util::into_raw(make_test_measurement::<i32>().into_any());
-        let measurement1 = util::into_raw(make_test_measurement::<i32>().into_any());
-        let basic_composition = Result::from(opendp_comb__make_basic_composition(measurement0, measurement1))?;
+        let measurement0 = util::into_raw(make_test_measurement::<i32>().into_any()) as AnyMeasurementPtr;
+        let measurement1 = util::into_raw(make_test_measurement::<i32>().into_any()) as AnyMeasurementPtr;
+        let measurements = AnyObject::new_raw(vec![measurement0, measurement1]);
+        let basic_composition = Result::from(opendp_comb__make_basic_composition(measurements))?;
         let arg = AnyObject::new_raw(999);
         let res = core::opendp_core__measurement_invoke(&basic_composition, arg);
-        let res: (AnyObject, AnyObject) = Fallible::from(res)?.downcast()?;
-        let res: (i32, i32) = (res.0.downcast()?, res.1.downcast()?);
-        assert_eq!(res, (999, 999));
+        let res: Vec<AnyObject> = Fallible::from(res)?.downcast()?;
+        let res = res.into_iter().map(|v| v.downcast()).collect::<Fallible<Vec<i32>>>()?;
+        assert_eq!(res, vec![999, 999]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_basic_composition_relation() -> Fallible<()> {
+        let measurement0 = util::into_raw(meas::make_base_gaussian_zcdp::<AllDomain<_>>(1.)?.into_any()) as AnyMeasurementPtr;
+        let measurement1 = util::into_raw(meas::make_base_gaussian_zcdp::<AllDomain<_>>(2.)?.into_any()) as AnyMeasurementPtr;
+        let measurements = AnyObject::new_raw(vec![measurement0, measurement1]);
+        let basic_composition = Result::from(opendp_comb__make_basic_composition(measurements))?;
+        let d_in = AnyMetricDistance::new(1.);
+        assert!(basic_composition.check(&d_in, &AnyMeasureDistance::new(0.625))?);
+        assert!(!basic_composition.check(&d_in, &AnyMeasureDistance::new(0.6))?);
         Ok(())
     }
 }
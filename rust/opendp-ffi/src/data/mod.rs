@@ -8,7 +8,7 @@ use opendp::{err, fallible};
 use opendp::data::Column;
 use opendp::error::Fallible;
 
-use crate::any::{AnyObject, Downcast, AnyMeasureDistance, AnyMetricDistance};
+use crate::any::{AnyObject, Downcast, AnyMeasureDistance, AnyMetricDistance, AnyMeasurementPtr};
 use crate::core::{FfiError, FfiResult, FfiSlice};
 use crate::util;
 use crate::util::{c_bool, Type, TypeContents};
@@ -149,6 +149,8 @@ pub extern "C" fn opendp_data__slice_as_object(raw: *const FfiSlice, T: *const c
             let element = try_!(Type::of_id(&element_id));
             if element.descriptor == "String" {
                 raw_to_vec_string(raw)
+            } else if element.descriptor == "AnyMeasurementPtr" {
+                raw_to_vec::<AnyMeasurementPtr>(raw)
             } else {
                 dispatch!(raw_to_vec, [(element, @primitives)], (raw))
             }
@@ -206,6 +208,16 @@ pub extern "C" fn opendp_data__object_as_slice(obj: *const AnyObject) -> FfiResu
         util::into_raw(vec);
         res
     }
+    fn vec_object_to_raw(obj: &AnyObject) -> Fallible<FfiSlice> {
+        let vec_obj: &Vec<AnyObject> = obj.downcast_ref()?;
+        let vec = vec_obj.iter()
+            .map(|v| v as *const AnyObject)
+            .collect::<Vec<*const AnyObject>>();
+
+        let res = Ok(FfiSlice::new(vec.as_ptr() as *mut c_void, vec.len()));
+        util::into_raw(vec);
+        res
+    }
     fn slice_to_raw<T>(_obj: &AnyObject) -> Fallible<FfiSlice> {
         // TODO: Need to get a reference to the slice here.
         unimplemented!()
@@ -246,6 +258,8 @@ pub extern "C" fn opendp_data__object_as_slice(obj: *const AnyObject) -> FfiResu
             let element = try_!(Type::of_id(element_id));
             if element.descriptor == "String" {
                 vec_string_to_raw(obj)
+            } else if element.descriptor == "AnyObject" {
+                vec_object_to_raw(obj)
             } else {
                 dispatch!(vec_to_raw, [(element, @primitives)], (obj))
             }
@@ -303,7 +317,7 @@ impl std::fmt::Debug for AnyObject {
         let type_arg = &self.type_;
         f.write_str(dispatch!(monomorphize, [(type_arg, [
             u32, u64, i32, i64, f32, f64, bool, String, u8, Column,
-            Vec<u32>, Vec<u64>, Vec<i32>, Vec<i64>, Vec<f32>, Vec<f64>, Vec<bool>, Vec<String>, Vec<u8>, Vec<Column>, Vec<Vec<String>>,
+            Vec<u32>, Vec<u64>, Vec<i32>, Vec<i64>, Vec<f32>, Vec<f64>, Vec<bool>, Vec<String>, Vec<u8>, Vec<Column>, Vec<Vec<String>>, Vec<AnyObject>,
             HashMap<String, Column>,
             // FIXME: The following are for Python demo use of compositions. Need to figure this out!!!
             (Box<i32>, Box<f64>),
@@ -0,0 +1,65 @@
+use std::convert::TryFrom;
+use std::os::raw::{c_char, c_void};
+
+use num::Float;
+
+use opendp::dom::{AllDomain, VectorDomain};
+use opendp::err;
+use opendp::meas::{DiscreteGaussianDomain, make_base_discrete_gaussian};
+
+use crate::any::AnyMeasurement;
+use crate::core::{FfiResult, IntoAnyMeasurementFfiResultExt};
+use crate::util::Type;
+use opendp::traits::{InfCast, MeasureDistance, MetricDistance};
+
+#[no_mangle]
+pub extern "C" fn opendp_meas__make_base_discrete_gaussian(
+    scale: *const c_void,
+    D: *const c_char, QO: *const c_char
+) -> FfiResult<*mut AnyMeasurement> {
+    fn monomorphize<D, QO>(scale: *const c_void) -> FfiResult<*mut AnyMeasurement>
+        where D: 'static + DiscreteGaussianDomain,
+              D::Atom: 'static + Clone + MetricDistance,
+              QO: 'static + Float + InfCast<D::Atom> + MeasureDistance,
+              f64: From<QO> {
+        let scale = *try_as_ref!(scale as *const QO);
+        make_base_discrete_gaussian::<D, QO>(scale).into_any()
+    }
+    let D = try_!(Type::try_from(D));
+    let QO = try_!(Type::try_from(QO));
+    dispatch!(monomorphize, [
+        (D, [
+            AllDomain<i8>, AllDomain<i16>, AllDomain<i32>, AllDomain<i64>, AllDomain<i128>,
+            VectorDomain<AllDomain<i8>>, VectorDomain<AllDomain<i16>>, VectorDomain<AllDomain<i32>>,
+            VectorDomain<AllDomain<i64>>, VectorDomain<AllDomain<i128>>
+        ]),
+        (QO, @floats)
+    ], (scale))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use opendp::error::Fallible;
+
+    use crate::any::{AnyObject, Downcast};
+    use crate::core;
+    use crate::util;
+    use crate::util::ToCharP;
+
+    use super::*;
+
+    #[test]
+    fn test_make_base_discrete_gaussian() -> Fallible<()> {
+        let measurement = Result::from(opendp_meas__make_base_discrete_gaussian(
+            util::into_raw(0.0) as *const c_void,
+            "AllDomain<i32>".to_char_p(),
+            "f64".to_char_p(),
+        ))?;
+        let arg = AnyObject::new_raw(99);
+        let res = core::opendp_core__measurement_invoke(&measurement, arg);
+        let res: i32 = Fallible::from(res)?.downcast()?;
+        assert_eq!(res, 99);
+        Ok(())
+    }
+}
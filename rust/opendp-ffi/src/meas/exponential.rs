@@ -0,0 +1,89 @@
+use std::convert::TryFrom;
+use std::os::raw::{c_char, c_void};
+
+use num::Float;
+
+use opendp::err;
+use opendp::meas::{make_base_exponential_candidates, make_base_permute_and_flip, SelectionNoise};
+use opendp::samplers::SampleUniform;
+use opendp::traits::{CheckNull, ExactIntCast, MeasureDistance, MetricDistance, TotalOrd};
+
+use crate::any::AnyMeasurement;
+use crate::core::{FfiResult, IntoAnyMeasurementFfiResultExt};
+use crate::util::Type;
+use crate::util;
+
+#[no_mangle]
+pub extern "C" fn opendp_meas__make_base_exponential_candidates(
+    scale: *const c_void, noise: *const c_char,
+    TIA: *const c_char, TO: *const c_char,
+) -> FfiResult<*mut AnyMeasurement> {
+    fn monomorphize<TIA, TO>(scale: *const c_void, noise: SelectionNoise) -> FfiResult<*mut AnyMeasurement>
+        where TIA: 'static + Float + SampleUniform + TotalOrd + CheckNull + MetricDistance + MeasureDistance,
+              TO: 'static + ExactIntCast<usize> + CheckNull {
+        let scale = *try_as_ref!(scale as *const TIA);
+        make_base_exponential_candidates::<TIA, TO>(scale, noise).into_any()
+    }
+    let noise = try_!(try_!(util::to_str(noise)).parse::<SelectionNoise>());
+    let TIA = try_!(Type::try_from(TIA));
+    let TO = try_!(Type::try_from(TO));
+    dispatch!(monomorphize, [(TIA, @floats), (TO, @integers)], (scale, noise))
+}
+
+#[no_mangle]
+pub extern "C" fn opendp_meas__make_base_permute_and_flip(
+    scale: *const c_void,
+    TIA: *const c_char, TO: *const c_char,
+) -> FfiResult<*mut AnyMeasurement> {
+    fn monomorphize<TIA, TO>(scale: *const c_void) -> FfiResult<*mut AnyMeasurement>
+        where TIA: 'static + Float + TotalOrd + CheckNull + MetricDistance + MeasureDistance,
+              TO: 'static + ExactIntCast<usize> + CheckNull,
+              f64: From<TIA> {
+        let scale = *try_as_ref!(scale as *const TIA);
+        make_base_permute_and_flip::<TIA, TO>(scale).into_any()
+    }
+    let TIA = try_!(Type::try_from(TIA));
+    let TO = try_!(Type::try_from(TO));
+    dispatch!(monomorphize, [(TIA, @floats), (TO, @integers)], (scale))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use opendp::error::Fallible;
+
+    use crate::any::{AnyObject, Downcast};
+    use crate::core;
+    use crate::util::ToCharP;
+
+    use super::*;
+
+    #[test]
+    fn test_make_base_exponential_candidates() -> Fallible<()> {
+        let measurement = Result::from(opendp_meas__make_base_exponential_candidates(
+            util::into_raw(0.1) as *const c_void,
+            "gumbel".to_char_p(),
+            "f64".to_char_p(),
+            "i32".to_char_p(),
+        ))?;
+        let arg = AnyObject::new_raw(vec![1.0, 100.0, 2.0]);
+        let res = core::opendp_core__measurement_invoke(&measurement, arg);
+        let res: i32 = Fallible::from(res)?.downcast()?;
+        assert_eq!(res, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_base_permute_and_flip() -> Fallible<()> {
+        let measurement = Result::from(opendp_meas__make_base_permute_and_flip(
+            util::into_raw(0.1) as *const c_void,
+            "f64".to_char_p(),
+            "i32".to_char_p(),
+        ))?;
+        let arg = AnyObject::new_raw(vec![1.0, 100.0, 2.0]);
+        let res = core::opendp_core__measurement_invoke(&measurement, arg);
+        let res: i32 = Fallible::from(res)?.downcast()?;
+        assert_eq!(res, 1);
+        Ok(())
+    }
+}
@@ -4,14 +4,14 @@ use std::os::raw::{c_char, c_void};
 use num::Float;
 
 use opendp::err;
-use opendp::meas::{make_base_gaussian, GaussianDomain};
+use opendp::meas::{make_base_gaussian, make_base_gaussian_zcdp, GaussianDomain};
 use opendp::samplers::SampleGaussian;
 
 use crate::any::AnyMeasurement;
 use crate::core::{FfiResult, IntoAnyMeasurementFfiResultExt};
 use crate::util::Type;
 use opendp::dom::{AllDomain, VectorDomain};
-use opendp::traits::{InfCast, CheckNull};
+use opendp::traits::{InfCast, CheckNull, MeasureDistance};
 
 #[no_mangle]
 pub extern "C" fn opendp_meas__make_base_gaussian(
@@ -20,7 +20,8 @@ pub extern "C" fn opendp_meas__make_base_gaussian(
 ) -> FfiResult<*mut AnyMeasurement> {
     fn monomorphize<D>(scale: *const c_void) -> FfiResult<*mut AnyMeasurement> where
         D: 'static + GaussianDomain,
-        D::Atom: 'static + Clone + SampleGaussian + Float + InfCast<f64> + CheckNull {
+        D::Atom: 'static + Clone + SampleGaussian + Float + InfCast<f64> + CheckNull,
+        (D::Atom, D::Atom): MeasureDistance {
         let scale = *try_as_ref!(scale as *const D::Atom);
         make_base_gaussian::<D>(scale).into_any()
     }
@@ -30,6 +31,23 @@ pub extern "C" fn opendp_meas__make_base_gaussian(
     ], (scale))
 }
 
+#[no_mangle]
+pub extern "C" fn opendp_meas__make_base_gaussian_zcdp(
+    scale: *const c_void,
+    D: *const c_char,
+) -> FfiResult<*mut AnyMeasurement> {
+    fn monomorphize<D>(scale: *const c_void) -> FfiResult<*mut AnyMeasurement> where
+        D: 'static + GaussianDomain,
+        D::Atom: 'static + Clone + SampleGaussian + Float + InfCast<f64> + CheckNull + MeasureDistance {
+        let scale = *try_as_ref!(scale as *const D::Atom);
+        make_base_gaussian_zcdp::<D>(scale).into_any()
+    }
+    let D = try_!(Type::try_from(D));
+    dispatch!(monomorphize, [
+        (D, [AllDomain<f64>, AllDomain<f32>, VectorDomain<AllDomain<f64>>, VectorDomain<AllDomain<f32>>])
+    ], (scale))
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -53,6 +71,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_make_base_gaussian_zcdp() -> Fallible<()> {
+        let measurement = Result::from(opendp_meas__make_base_gaussian_zcdp(
+            util::into_raw(0.0) as *const c_void, "AllDomain<f64>".to_char_p()))?;
+        let arg = AnyObject::new_raw(1.0);
+        let res = core::opendp_core__measurement_invoke(&measurement, arg);
+        let res: f64 = Fallible::from(res)?.downcast()?;
+        assert_eq!(res, 1.0);
+        Ok(())
+    }
+
     #[test]
     fn test_make_base_gaussian_vec() -> Fallible<()> {
         let measurement = Result::from(opendp_meas__make_base_gaussian(
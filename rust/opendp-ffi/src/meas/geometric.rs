@@ -11,7 +11,7 @@ use crate::any::{AnyMeasurement, AnyObject, Downcast};
 use crate::core::{FfiResult, IntoAnyMeasurementFfiResultExt};
 use crate::util::Type;
 use crate::util;
-use opendp::traits::{InfCast, TotalOrd};
+use opendp::traits::{InfCast, TotalOrd, MeasureDistance};
 
 #[no_mangle]
 pub extern "C" fn opendp_meas__make_base_geometric(
@@ -24,7 +24,7 @@ pub extern "C" fn opendp_meas__make_base_geometric(
     ) -> FfiResult<*mut AnyMeasurement>
         where D: 'static + GeometricDomain,
               D::Atom: 'static + InfCast<QO> + TotalOrd + Clone,
-              QO: 'static + Float + InfCast<D::Atom> + TotalOrd,
+              QO: 'static + Float + InfCast<D::Atom> + TotalOrd + MeasureDistance,
               f64: From<QO> {
         let scale = try_as_ref!(scale as *const QO).clone();
         let bounds = if let Some(bounds) = util::as_ref(bounds) {
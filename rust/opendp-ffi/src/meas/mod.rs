@@ -1,8 +1,18 @@
 #[cfg(all(feature="floating-point", feature="contrib"))]
 pub mod gaussian;
 #[cfg(feature="contrib")]
+pub mod discrete_gaussian;
+#[cfg(feature="contrib")]
 pub mod geometric;
 #[cfg(all(feature="floating-point", feature="contrib"))]
 pub mod laplace;
 #[cfg(all(feature="floating-point", feature="contrib"))]
+pub mod exponential;
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub mod quantile;
+#[cfg(feature="contrib")]
+pub mod randomized_response;
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub mod snapping;
+#[cfg(all(feature="floating-point", feature="contrib"))]
 pub mod stability;
@@ -0,0 +1,88 @@
+use std::convert::TryFrom;
+use std::os::raw::{c_char, c_void};
+
+use num::Float;
+
+use opendp::dist::IntDistance;
+use opendp::err;
+use opendp::meas::{make_quantile, make_quantiles};
+use opendp::samplers::SampleUniform;
+use opendp::traits::{CheckNull, ExactIntCast, InfCast, MeasureDistance, TotalOrd};
+
+use crate::any::{AnyMeasurement, AnyObject, Downcast};
+use crate::core::{FfiResult, IntoAnyMeasurementFfiResultExt};
+use crate::util::Type;
+
+#[no_mangle]
+pub extern "C" fn opendp_meas__make_quantile(
+    bounds: *const AnyObject, alpha: *const c_void, scale: *const c_void,
+    T: *const c_char,
+) -> FfiResult<*mut AnyMeasurement> {
+    fn monomorphize<T>(bounds: *const AnyObject, alpha: *const c_void, scale: *const c_void) -> FfiResult<*mut AnyMeasurement>
+        where T: 'static + Float + SampleUniform + TotalOrd + CheckNull + ExactIntCast<usize> + InfCast<IntDistance> + MeasureDistance {
+        let bounds = *try_!(try_as_ref!(bounds).downcast_ref::<(T, T)>());
+        let alpha = *try_as_ref!(alpha as *const T);
+        let scale = *try_as_ref!(scale as *const T);
+        make_quantile::<T>(bounds, alpha, scale).into_any()
+    }
+    let T = try_!(Type::try_from(T));
+    dispatch!(monomorphize, [(T, @floats)], (bounds, alpha, scale))
+}
+
+#[no_mangle]
+pub extern "C" fn opendp_meas__make_quantiles(
+    bounds: *const AnyObject, alphas: *const AnyObject, scale: *const c_void,
+    T: *const c_char,
+) -> FfiResult<*mut AnyMeasurement> {
+    fn monomorphize<T>(bounds: *const AnyObject, alphas: *const AnyObject, scale: *const c_void) -> FfiResult<*mut AnyMeasurement>
+        where T: 'static + Float + SampleUniform + TotalOrd + CheckNull + ExactIntCast<usize> + InfCast<IntDistance> + MeasureDistance {
+        let bounds = *try_!(try_as_ref!(bounds).downcast_ref::<(T, T)>());
+        let alphas = try_!(try_as_ref!(alphas).downcast_ref::<Vec<T>>()).clone();
+        let scale = *try_as_ref!(scale as *const T);
+        make_quantiles::<T>(bounds, alphas, scale).into_any()
+    }
+    let T = try_!(Type::try_from(T));
+    dispatch!(monomorphize, [(T, @floats)], (bounds, alphas, scale))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use opendp::error::Fallible;
+
+    use crate::core;
+    use crate::util;
+    use crate::util::ToCharP;
+
+    use super::*;
+
+    #[test]
+    fn test_make_quantile() -> Fallible<()> {
+        let measurement = Result::from(opendp_meas__make_quantile(
+            util::into_raw(AnyObject::new((0.0, 10.0))),
+            util::into_raw(0.5) as *const c_void,
+            util::into_raw(1.0) as *const c_void,
+            "f64".to_char_p(),
+        ))?;
+        let arg = AnyObject::new_raw(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        let res = core::opendp_core__measurement_invoke(&measurement, arg);
+        let res: f64 = Fallible::from(res)?.downcast()?;
+        assert!((0.0..=10.0).contains(&res));
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_quantiles() -> Fallible<()> {
+        let measurement = Result::from(opendp_meas__make_quantiles(
+            util::into_raw(AnyObject::new((0.0, 10.0))),
+            AnyObject::new_raw(vec![0.25, 0.5, 0.75]),
+            util::into_raw(1.0) as *const c_void,
+            "f64".to_char_p(),
+        ))?;
+        let arg = AnyObject::new_raw(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        let res = core::opendp_core__measurement_invoke(&measurement, arg);
+        let res: Vec<f64> = Fallible::from(res)?.downcast()?;
+        assert_eq!(res.len(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use std::convert::TryFrom;
+use std::hash::Hash;
+use std::os::raw::{c_char, c_void};
+
+use num::Float;
+
+use opendp::err;
+use opendp::meas::{make_randomized_response, make_randomized_response_bool};
+use opendp::traits::{CheckNull, ExactIntCast, MeasureDistance};
+
+use crate::any::{AnyMeasurement, AnyObject, Downcast};
+use crate::core::{FfiResult, IntoAnyMeasurementFfiResultExt};
+use crate::util::{c_bool, Type};
+use crate::util;
+
+#[no_mangle]
+pub extern "C" fn opendp_meas__make_randomized_response_bool(
+    prob: *const c_void, constant_time: c_bool,
+    Q: *const c_char,
+) -> FfiResult<*mut AnyMeasurement> {
+    fn monomorphize<Q>(prob: *const c_void, constant_time: bool) -> FfiResult<*mut AnyMeasurement>
+        where Q: 'static + Float + MeasureDistance,
+              f64: From<Q> {
+        let prob = *try_as_ref!(prob as *const Q);
+        make_randomized_response_bool::<Q>(prob, constant_time).into_any()
+    }
+    let Q = try_!(Type::try_from(Q));
+    let constant_time = util::to_bool(constant_time);
+    dispatch!(monomorphize, [(Q, @floats)], (prob, constant_time))
+}
+
+#[no_mangle]
+pub extern "C" fn opendp_meas__make_randomized_response(
+    categories: *const AnyObject, prob: *const c_void, constant_time: c_bool,
+    T: *const c_char, Q: *const c_char,
+) -> FfiResult<*mut AnyMeasurement> {
+    fn monomorphize<T, Q>(categories: *const AnyObject, prob: *const c_void, constant_time: bool) -> FfiResult<*mut AnyMeasurement>
+        where T: 'static + Clone + Eq + Hash + CheckNull,
+              Q: 'static + Float + ExactIntCast<usize> + MeasureDistance,
+              f64: From<Q> {
+        let categories = try_!(try_as_ref!(categories).downcast_ref::<Vec<T>>()).clone();
+        let prob = *try_as_ref!(prob as *const Q);
+        make_randomized_response::<T, Q>(categories, prob, constant_time).into_any()
+    }
+    let T = try_!(Type::try_from(T));
+    let Q = try_!(Type::try_from(Q));
+    let constant_time = util::to_bool(constant_time);
+    dispatch!(monomorphize, [(T, @hashable), (Q, @floats)], (categories, prob, constant_time))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use opendp::error::Fallible;
+
+    use crate::core;
+    use crate::util::ToCharP;
+
+    use super::*;
+
+    #[test]
+    fn test_make_randomized_response_bool() -> Fallible<()> {
+        let measurement = Result::from(opendp_meas__make_randomized_response_bool(
+            util::into_raw(0.75) as *const c_void,
+            util::from_bool(false),
+            "f64".to_char_p(),
+        ))?;
+        let arg = AnyObject::new_raw(true);
+        let res = core::opendp_core__measurement_invoke(&measurement, arg);
+        let _res: bool = Fallible::from(res)?.downcast()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_randomized_response() -> Fallible<()> {
+        let measurement = Result::from(opendp_meas__make_randomized_response(
+            AnyObject::new_raw(vec!["A".to_string(), "B".to_string(), "C".to_string()]),
+            util::into_raw(0.75) as *const c_void,
+            util::from_bool(false),
+            "String".to_char_p(),
+            "f64".to_char_p(),
+        ))?;
+        let arg = AnyObject::new_raw("A".to_string());
+        let res = core::opendp_core__measurement_invoke(&measurement, arg);
+        let res: String = Fallible::from(res)?.downcast()?;
+        assert!(["A", "B", "C"].contains(&res.as_str()));
+        Ok(())
+    }
+}
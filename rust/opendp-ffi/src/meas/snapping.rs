@@ -0,0 +1,32 @@
+use opendp::meas::make_base_snapping;
+
+use crate::any::AnyMeasurement;
+use crate::core::{FfiResult, IntoAnyMeasurementFfiResultExt};
+
+#[no_mangle]
+pub extern "C" fn opendp_meas__make_base_snapping(
+    scale: f64, bound: f64,
+) -> FfiResult<*mut AnyMeasurement> {
+    make_base_snapping(scale, bound).into_any()
+}
+
+
+#[cfg(all(test, feature="use-mpfr"))]
+mod tests {
+    use opendp::error::Fallible;
+
+    use crate::any::{AnyObject, Downcast};
+    use crate::core;
+
+    use super::*;
+
+    #[test]
+    fn test_make_base_snapping() -> Fallible<()> {
+        let measurement = Result::from(opendp_meas__make_base_snapping(1.0, 10.0))?;
+        let arg = AnyObject::new_raw(99.0);
+        let res = core::opendp_core__measurement_invoke(&measurement, arg);
+        let res: f64 = Fallible::from(res)?.downcast()?;
+        assert!((-10. ..=10.).contains(&res));
+        Ok(())
+    }
+}
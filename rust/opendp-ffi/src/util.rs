@@ -8,9 +8,9 @@ use std::os::raw::c_char;
 use std::str::Utf8Error;
 
 use opendp::{err, fallible};
-use opendp::dist::{SubstituteDistance, L1Distance, L2Distance, SymmetricDistance, AbsoluteDistance};
+use opendp::dist::{SubstituteDistance, DiscreteDistance, L1Distance, L2Distance, LInfDistance, SymmetricDistance, AbsoluteDistance, MaxDivergence, SmoothedMaxDivergence, ZeroConcentratedDivergence};
 use opendp::error::*;
-use crate::any::AnyObject;
+use crate::any::{AnyMeasurementPtr, AnyObject};
 use opendp::dom::{VectorDomain, AllDomain, BoundedDomain, InherentNullDomain, OptionNullDomain, SizedDomain};
 
 #[derive(Debug, PartialEq, Clone)]
@@ -94,7 +94,7 @@ impl Type {
     }
     pub fn get_sensitivity_distance(&self) -> Fallible<Type> {
         if let TypeContents::GENERIC {args, name} = &self.contents {
-            if !vec!["L1Distance", "L2Distance", "AbsoluteDistance"].contains(name) {
+            if !vec!["L1Distance", "L2Distance", "LInfDistance", "AbsoluteDistance"].contains(name) {
                 return fallible!(TypeParse, "Expected a sensitivity type name, received {:?}", name)
             }
             if args.len() != 1 {
@@ -109,7 +109,7 @@ impl Type {
         if self == &Type::of::<SubstituteDistance>() || self == &Type::of::<SymmetricDistance>() {
             Ok(MetricClass::Dataset)
         } else if let TypeContents::GENERIC { name, .. } = &self.contents {
-            if vec!["L1Distance", "L2Distance", "AbsoluteDistance"].contains(name) {
+            if vec!["L1Distance", "L2Distance", "LInfDistance", "AbsoluteDistance"].contains(name) {
                 Ok(MetricClass::Sensitivity)
             } else {
                 return fallible!(TypeParse, "Expected a metric type name, received {:?}", name)
@@ -237,6 +237,8 @@ lazy_static! {
             type_vec![[bool, char, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64, String, AnyObject]],
             type_vec![Vec, <bool, char, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64, String, AnyObject>],
             type_vec![HashMap, <bool, char, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, String>, <bool, char, u8, u16, u32, i16, i32, i64, i128, f32, f64, String, AnyObject>],
+            type_vec![AnyMeasurementPtr],
+            type_vec![Vec, <AnyMeasurementPtr>],
             // OptionNullDomain<AllDomain<_>>::Carrier
             type_vec![[Vec Option], <bool, char, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64, String, AnyObject>],
 
@@ -251,10 +253,16 @@ lazy_static! {
             type_vec![[SizedDomain VectorDomain AllDomain], <bool, char, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64, String>],
 
             // metrics
-            type_vec![SubstituteDistance, SymmetricDistance],
+            type_vec![SubstituteDistance, SymmetricDistance, DiscreteDistance],
             type_vec![AbsoluteDistance, <u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64>],
             type_vec![L1Distance, <u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64>],
             type_vec![L2Distance, <u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64>],
+            type_vec![LInfDistance, <u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64>],
+
+            // measures
+            type_vec![MaxDivergence, <f32, f64>],
+            type_vec![SmoothedMaxDivergence, <f32, f64>],
+            type_vec![ZeroConcentratedDivergence, <f32, f64>],
         ].into_iter().flatten().collect();
         let descriptors: HashSet<_> = types.iter().map(|e| &e.descriptor).collect();
         assert_eq!(descriptors.len(), types.len());
@@ -335,9 +343,9 @@ pub fn to_option_str<'a>(p: *const c_char) -> Fallible<Option<&'a str>> {
 #[allow(non_camel_case_types)]
 pub type c_bool = u8;  // PLATFORM DEPENDENT!!!
 
-// pub fn to_bool(b: c_bool) -> bool {
-//     b != 0
-// }
+pub fn to_bool(b: c_bool) -> bool {
+    b != 0
+}
 
 pub fn from_bool(b: bool) -> c_bool {
     if b {1} else {0}
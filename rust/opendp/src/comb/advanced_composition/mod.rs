@@ -0,0 +1,173 @@
+use num::{Float, One, Zero};
+
+use crate::core::{Domain, Function, Measure, Measurement, Metric, PrivacyRelation};
+use crate::dist::{MaxDivergence, SmoothedMaxDivergence};
+use crate::dom::VectorDomain;
+use crate::error::Fallible;
+use crate::traits::ExactIntCast;
+
+/// Measures whose privacy losses can be expressed as (epsilon, delta), for use in advanced composition.
+pub trait AdvancedCompositionMeasure: Measure {
+    type Atom;
+
+    /// Express the privacy loss `d` as (epsilon, delta).
+    fn to_approximate(d: &Self::Distance) -> (Self::Atom, Self::Atom);
+
+    /// The largest privacy loss that is no greater than (`epsilon`, `delta`).
+    fn from_approximate(epsilon: Self::Atom, delta: Self::Atom) -> Self::Distance;
+}
+
+impl<Q: Float> AdvancedCompositionMeasure for MaxDivergence<Q> {
+    type Atom = Q;
+    fn to_approximate(&epsilon: &Q) -> (Q, Q) {
+        (epsilon, Q::zero())
+    }
+    fn from_approximate(epsilon: Q, _delta: Q) -> Q {
+        epsilon
+    }
+}
+
+impl<Q: Float> AdvancedCompositionMeasure for SmoothedMaxDivergence<Q> {
+    type Atom = Q;
+    fn to_approximate(&d: &(Q, Q)) -> (Q, Q) {
+        d
+    }
+    fn from_approximate(epsilon: Q, delta: Q) -> (Q, Q) {
+        (epsilon, delta)
+    }
+}
+
+/// The epsilon of `k` compositions of an `epsilon`-DP mechanism, under the advanced composition theorem:
+/// sqrt(2k ln(1 / delta_prime)) * epsilon + k * epsilon * (e^epsilon - 1).
+fn advanced_epsilon<Q: Float>(epsilon: Q, k: Q, delta_prime: Q) -> Q {
+    let two = Q::one() + Q::one();
+    (two * k * delta_prime.recip().ln()).sqrt() * epsilon + k * epsilon * epsilon.exp_m1()
+}
+
+/// The largest per-query epsilon whose advanced composition does not exceed `epsilon`.
+fn advanced_epsilon_inverse<Q: Float>(epsilon: Q, k: Q, delta_prime: Q) -> Q {
+    // the composed epsilon is at least the per-query epsilon, so the answer lies in [0, epsilon]
+    let (mut lower, mut upper) = (Q::zero(), epsilon);
+    let two = Q::one() + Q::one();
+    loop {
+        let mid = lower + (upper - lower) / two;
+        if mid <= lower || mid >= upper {
+            return lower
+        }
+        if advanced_epsilon(mid, k, delta_prime) <= epsilon {
+            lower = mid
+        } else {
+            upper = mid
+        }
+    }
+}
+
+/// Construct the composition of `k` releases from `measurement`, under the advanced composition theorem
+/// of Dwork, Rothblum and Vadhan. The output is a vector, with one release per query.
+///
+/// The composition is (sqrt(2k ln(1 / `delta_prime`)) ε + k ε (e^ε - 1), k δ + `delta_prime`)-DP
+/// when `measurement` is (ε, δ)-DP. For small `k`, basic composition may be tighter.
+pub fn make_advanced_composition<DI, DO, MI, MO>(
+    measurement: &Measurement<DI, DO, MI, MO>, k: usize, delta_prime: MO::Atom,
+) -> Fallible<Measurement<DI, VectorDomain<DO>, MI, SmoothedMaxDivergence<MO::Atom>>>
+    where DI: 'static + Domain,
+          DO: 'static + Domain,
+          MI: 'static + Metric,
+          MO: 'static + AdvancedCompositionMeasure,
+          MO::Atom: 'static + Float + ExactIntCast<usize> {
+    if k == 0 {
+        return fallible!(MakeMeasurement, "k must be positive")
+    }
+    if delta_prime.is_nan() || delta_prime <= MO::Atom::zero() || delta_prime >= MO::Atom::one() {
+        return fallible!(MakeMeasurement, "delta_prime must be in (0, 1)")
+    }
+    let num_queries = k;
+    let k = MO::Atom::exact_int_cast(k)?;
+
+    let function = measurement.function.clone();
+    let PrivacyRelation { relation, forward_map, backward_map } = measurement.privacy_relation.clone();
+
+    Ok(Measurement::new(
+        measurement.input_domain.clone(),
+        VectorDomain::new(measurement.output_domain.clone()),
+        Function::new_fallible(move |arg: &DI::Carrier| (0..num_queries).map(|_| function.eval(arg)).collect()),
+        measurement.input_metric.clone(),
+        SmoothedMaxDivergence::default(),
+        PrivacyRelation::new_all(
+            // relation
+            move |d_in: &MI::Distance, &(epsilon, delta): &(MO::Atom, MO::Atom)| {
+                if epsilon.is_sign_negative() {
+                    return fallible!(InvalidDistance, "epsilon must be non-negative")
+                }
+                if delta.is_sign_negative() {
+                    return fallible!(InvalidDistance, "delta must be non-negative")
+                }
+                if delta < delta_prime {
+                    return Ok(false)
+                }
+                let epsilon_query = advanced_epsilon_inverse(epsilon, k, delta_prime);
+                relation(d_in, &MO::from_approximate(epsilon_query, (delta - delta_prime) / k))
+            },
+            // forward map
+            forward_map.map(|forward_map| move |d_in: &MI::Distance| {
+                let (epsilon, delta) = MO::to_approximate(&*forward_map(d_in)?);
+                Ok(Box::new((advanced_epsilon(epsilon, k, delta_prime), k * delta + delta_prime)))
+            }),
+            // backward map
+            backward_map.map(|backward_map| move |&(epsilon, delta): &(MO::Atom, MO::Atom)| {
+                if delta < delta_prime {
+                    return fallible!(FailedRelation, "delta must be at least delta_prime")
+                }
+                let epsilon_query = advanced_epsilon_inverse(epsilon, k, delta_prime);
+                backward_map(&MO::from_approximate(epsilon_query, (delta - delta_prime) / k))
+            }),
+        )
+    ))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::comb::make_basic_composition;
+    use crate::dom::AllDomain;
+    use crate::error::ExplainUnwrap;
+    use crate::meas::make_base_laplace;
+
+    use super::*;
+
+    #[test]
+    fn test_make_advanced_composition() -> Fallible<()> {
+        let laplace = make_base_laplace::<AllDomain<f64>>(10.)?;
+        let composition = make_advanced_composition(&laplace, 100, 1e-6)?;
+        assert_eq!(composition.invoke(&0.)?.len(), 100);
+
+        // each query is 0.1-DP, so epsilon = sqrt(200 ln(1e6)) * 0.1 + 10 * (e^0.1 - 1) ~= 6.3082
+        let (epsilon, delta) = *composition.privacy_relation.forward_map.as_ref().unwrap_test()(&1.)?;
+        assert!((epsilon - 6.3082).abs() < 1e-4);
+        assert_eq!(delta, 1e-6);
+        assert!(composition.check(&1., &(6.31, 1e-6))?);
+        assert!(!composition.check(&1., &(6.30, 1e-6))?);
+        assert!(!composition.check(&1., &(100., 1e-7))?);
+
+        // much tighter than basic composition, which would be 10-DP
+        let laplaces = vec![&laplace; 100];
+        assert!(!make_basic_composition(laplaces)?.check(&1., &9.9)?);
+
+        let backward_map = composition.privacy_relation.backward_map.as_ref().unwrap_test();
+        assert!((*backward_map(&(6.3082, 1e-6))? - 1.).abs() < 1e-4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_advanced_composition_approximate() -> Fallible<()> {
+        let laplace = make_base_laplace::<AllDomain<f64>>(10.)?;
+        let base = make_advanced_composition(&laplace, 10, 1e-6)?;
+        // compose the (epsilon, delta)-DP composition again, so that the member deltas add up
+        let composition = make_advanced_composition(&base, 10, 1e-6)?;
+        let (_epsilon, delta) = *composition.privacy_relation.forward_map.as_ref().unwrap_test()(&1.)?;
+        assert!((delta - 1.1e-5).abs() < 1e-12);
+        assert!(make_advanced_composition(&laplace, 0, 1e-6).is_err());
+        assert!(make_advanced_composition(&laplace, 10, 0.).is_err());
+        Ok(())
+    }
+}
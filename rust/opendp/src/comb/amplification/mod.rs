@@ -0,0 +1,193 @@
+use num::{Float, NumCast, One, Zero};
+
+use crate::core::{Domain, Function, Measure, Measurement, PrivacyRelation};
+use crate::dist::{IntDistance, MaxDivergence, SmoothedMaxDivergence, SymmetricDistance};
+use crate::dom::{SizedDomain, VectorDomain};
+use crate::error::Fallible;
+use crate::traits::ExactIntCast;
+use crate::trans::{make_subsample_poisson, make_subsample_without_replacement};
+
+/// Measures whose privacy losses are amplified by subsampling.
+pub trait AmplifiableMeasure: Measure {
+    type Atom;
+
+    /// The privacy loss of a mechanism with privacy loss `d`, when each differing record
+    /// is only seen with probability at most `eta`.
+    fn amplify(d: &Self::Distance, eta: Self::Atom) -> Self::Distance;
+
+    /// The largest privacy loss whose amplification by `eta` is no greater than `d`.
+    fn deamplify(d: &Self::Distance, eta: Self::Atom) -> Self::Distance;
+}
+
+impl<Q: Float> AmplifiableMeasure for MaxDivergence<Q> {
+    type Atom = Q;
+    fn amplify(&epsilon: &Q, eta: Q) -> Q {
+        (eta * epsilon.exp_m1()).ln_1p()
+    }
+    fn deamplify(&epsilon: &Q, eta: Q) -> Q {
+        (epsilon.exp_m1() / eta).ln_1p()
+    }
+}
+
+impl<Q: Float> AmplifiableMeasure for SmoothedMaxDivergence<Q> {
+    type Atom = Q;
+    fn amplify(&(epsilon, delta): &(Q, Q), eta: Q) -> (Q, Q) {
+        (MaxDivergence::amplify(&epsilon, eta), eta * delta)
+    }
+    fn deamplify(&(epsilon, delta): &(Q, Q), eta: Q) -> (Q, Q) {
+        (MaxDivergence::deamplify(&epsilon, eta), delta / eta)
+    }
+}
+
+/// Builds the privacy relation of a subsampled measurement from the relation of `measurement`,
+/// where `eta` gives the probability that any of the differing records are sampled, at distance d_in.
+///
+/// When eta is zero (as at d_in = 0), the relation of `measurement` is used unchanged, instead of deamplifying by zero.
+fn make_amplified_relation<MO>(
+    relation: PrivacyRelation<SymmetricDistance, MO>,
+    eta: impl Fn(IntDistance) -> Fallible<MO::Atom> + 'static + Clone,
+) -> PrivacyRelation<SymmetricDistance, MO>
+    where MO: 'static + AmplifiableMeasure,
+          MO::Atom: 'static + Float {
+    let PrivacyRelation { relation, forward_map, .. } = relation;
+    let eta_forward = eta.clone();
+    PrivacyRelation::new_all(
+        // relation
+        move |&d_in: &IntDistance, d_out: &MO::Distance| {
+            let eta = eta(d_in)?;
+            if eta.is_zero() { return relation(&d_in, d_out) }
+            relation(&d_in, &MO::deamplify(d_out, eta))
+        },
+        // forward map
+        forward_map.map(|forward_map| move |&d_in: &IntDistance| {
+            let eta = eta_forward(d_in)?;
+            let d_out = forward_map(&d_in)?;
+            Ok(if eta.is_zero() { d_out } else { Box::new(MO::amplify(&*d_out, eta)) })
+        }),
+        None::<fn(&_) -> _>)
+}
+
+/// Construct a Measurement that applies `measurement` to a Poisson subsample of the data,
+/// where each record is kept with probability `rate`.
+///
+/// If `measurement` is ε-DP at d_in, then the subsampled measurement is ln(1 + η(e^ε - 1))-DP,
+/// where η = 1 - (1 - `rate`)^d_in is the probability that any of the d_in differing records are sampled.
+/// Any δ is scaled by η.
+pub fn make_poisson_amplification<DA, DO, MO>(
+    measurement: &Measurement<VectorDomain<DA>, DO, SymmetricDistance, MO>, rate: f64,
+) -> Fallible<Measurement<VectorDomain<DA>, DO, SymmetricDistance, MO>>
+    where DA: 'static + Clone + Domain,
+          DA::Carrier: 'static + Clone,
+          DO: 'static + Domain,
+          MO: 'static + AmplifiableMeasure,
+          MO::Atom: 'static + Float {
+    let subsample = make_subsample_poisson(measurement.input_domain.element_domain.clone(), rate)?;
+    let rate = <MO::Atom as NumCast>::from(rate)
+        .ok_or_else(|| err!(MakeMeasurement, "rate must be representable in the privacy loss type"))?;
+    let eta = move |d_in: IntDistance| -> Fallible<MO::Atom> {
+        Ok(MO::Atom::one() - (MO::Atom::one() - rate).powi(i32::exact_int_cast(d_in)?))
+    };
+    let function = Function::make_chain(&measurement.function, &subsample.function);
+
+    Ok(Measurement::new(
+        subsample.input_domain,
+        measurement.output_domain.clone(),
+        function,
+        SymmetricDistance,
+        measurement.output_measure.clone(),
+        make_amplified_relation(measurement.privacy_relation.clone(), eta)))
+}
+
+/// Construct a Measurement that applies `measurement` to a sample drawn without replacement from a dataset of `size` records.
+/// The size of the sample is the size of the input domain of `measurement`.
+///
+/// Neighboring datasets differ by s = d_in / 2 substitutions. If `measurement` is ε-DP at d_in,
+/// then the subsampled measurement is ln(1 + η(e^ε - 1))-DP, where η is the probability that any of the s substituted records are sampled.
+/// Any δ is scaled by η.
+pub fn make_without_replacement_amplification<DA, DO, MO>(
+    measurement: &Measurement<SizedDomain<VectorDomain<DA>>, DO, SymmetricDistance, MO>, size: usize,
+) -> Fallible<Measurement<SizedDomain<VectorDomain<DA>>, DO, SymmetricDistance, MO>>
+    where DA: 'static + Clone + Domain,
+          DA::Carrier: 'static + Clone,
+          DO: 'static + Domain,
+          MO: 'static + AmplifiableMeasure,
+          MO::Atom: 'static + Float {
+    let sample_size = measurement.input_domain.size;
+    let subsample = make_subsample_without_replacement(
+        size, sample_size, measurement.input_domain.element_domain.element_domain.clone())?;
+    let eta = move |d_in: IntDistance| -> Fallible<MO::Atom> {
+        let substitutions = (d_in / 2 + d_in % 2) as usize;
+        // the probability that none of the substituted records are sampled
+        let mut p_unsampled = MO::Atom::one();
+        for i in 0..sample_size {
+            if substitutions + i >= size {
+                return Ok(MO::Atom::one())
+            }
+            let ratio = <MO::Atom as NumCast>::from(size - substitutions - i)
+                .zip(<MO::Atom as NumCast>::from(size - i))
+                .ok_or_else(|| err!(FailedRelation, "size must be representable in the privacy loss type"))?;
+            p_unsampled = p_unsampled * (ratio.0 / ratio.1);
+        }
+        Ok(MO::Atom::one() - p_unsampled)
+    };
+    let function = Function::make_chain(&measurement.function, &subsample.function);
+
+    Ok(Measurement::new(
+        subsample.input_domain,
+        measurement.output_domain.clone(),
+        function,
+        SymmetricDistance,
+        measurement.output_measure.clone(),
+        make_amplified_relation(measurement.privacy_relation.clone(), eta)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ExplainUnwrap;
+    use crate::meas::make_base_laplace;
+    use crate::trans::{make_count, make_sized_bounded_sum};
+
+    use super::*;
+
+    #[test]
+    fn test_make_poisson_amplification() -> Fallible<()> {
+        // a 1-DP count
+        let count = (make_count::<i32, f64>()? >> make_base_laplace(1.)?)?;
+        let amplified = make_poisson_amplification(&count, 0.1)?;
+        amplified.invoke(&vec![1, 2, 3])?;
+
+        // ln(1 + 0.1 (e - 1)) ~= 0.1586
+        let epsilon = *amplified.privacy_relation.forward_map.as_ref().unwrap_test()(&1)?;
+        assert!((epsilon - 0.1586).abs() < 1e-4);
+        assert!(amplified.check(&1, &0.1587)?);
+        assert!(!amplified.check(&1, &0.1585)?);
+        // the full rate gives no amplification
+        assert!(!make_poisson_amplification(&count, 1.)?.check(&1, &0.99)?);
+        assert!(make_poisson_amplification(&count, 0.).is_err());
+
+        // neighbors at distance zero are identical, so no records differ
+        assert!(amplified.check(&0, &0.)?);
+        assert_eq!(amplified.privacy_relation.forward_map.as_ref().unwrap_test()(&0)?, Box::new(0.));
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_without_replacement_amplification() -> Fallible<()> {
+        // a sum over samples of 10 records in [0, 2], that is 2-DP when one record is substituted
+        let sum = (make_sized_bounded_sum(10, (0., 2.))? >> make_base_laplace(1.)?)?;
+        let amplified = make_without_replacement_amplification(&sum, 100)?;
+        assert_eq!(amplified.input_domain.size, 100);
+        amplified.invoke(&vec![1.; 100])?;
+        assert!(amplified.invoke(&vec![1.; 10]).is_err());
+
+        // the substituted record is sampled with probability 10 / 100, so epsilon = ln(1 + 0.1 (e^2 - 1)) ~= 0.4940
+        let epsilon = *amplified.privacy_relation.forward_map.as_ref().unwrap_test()(&2)?;
+        assert!((epsilon - 0.4940).abs() < 1e-4);
+        assert!(amplified.check(&2, &0.4941)?);
+        assert!(!amplified.check(&2, &0.4939)?);
+
+        assert!(amplified.check(&0, &0.)?);
+        Ok(())
+    }
+}
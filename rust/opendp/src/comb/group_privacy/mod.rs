@@ -0,0 +1,116 @@
+use num::Float;
+
+use crate::core::{Domain, Measure, Measurement, PrivacyRelation};
+use crate::dist::{IntDistance, MaxDivergence, SmoothedMaxDivergence, SymmetricDistance};
+use crate::error::Fallible;
+use crate::traits::ExactIntCast;
+
+/// Measures whose privacy losses can be extended from individuals to groups.
+pub trait GroupPrivacyMeasure: Measure {
+    type Atom;
+
+    /// The privacy loss for groups of `group_size` individuals, of a mechanism with privacy loss `d` for individuals.
+    fn group(d: &Self::Distance, group_size: Self::Atom) -> Self::Distance;
+
+    /// The largest privacy loss for individuals whose loss for groups of `group_size` individuals is no greater than `d`.
+    fn ungroup(d: &Self::Distance, group_size: Self::Atom) -> Self::Distance;
+}
+
+impl<Q: Float> GroupPrivacyMeasure for MaxDivergence<Q> {
+    type Atom = Q;
+    fn group(&epsilon: &Q, k: Q) -> Q {
+        k * epsilon
+    }
+    fn ungroup(&epsilon: &Q, k: Q) -> Q {
+        epsilon / k
+    }
+}
+
+impl<Q: Float> GroupPrivacyMeasure for SmoothedMaxDivergence<Q> {
+    type Atom = Q;
+    fn group(&(epsilon, delta): &(Q, Q), k: Q) -> (Q, Q) {
+        (k * epsilon, k * ((k - Q::one()) * epsilon).exp() * delta)
+    }
+    fn ungroup(&(epsilon, delta): &(Q, Q), k: Q) -> (Q, Q) {
+        let epsilon = epsilon / k;
+        (epsilon, delta / (k * ((k - Q::one()) * epsilon).exp()))
+    }
+}
+
+/// Construct a Measurement whose input distance counts groups of up to `group_size` individuals, like households,
+/// instead of individuals.
+///
+/// If `measurement` is ε-DP, the group measurement is kε-DP, where k = `group_size`.
+/// If `measurement` is (ε, δ)-DP, the group measurement is (kε, k e^{(k-1)ε} δ)-DP.
+pub fn make_group_privacy<DI, DO, MO>(
+    measurement: &Measurement<DI, DO, SymmetricDistance, MO>, group_size: IntDistance,
+) -> Fallible<Measurement<DI, DO, SymmetricDistance, MO>>
+    where DI: 'static + Domain,
+          DO: 'static + Domain,
+          MO: 'static + GroupPrivacyMeasure,
+          MO::Atom: 'static + Float + ExactIntCast<IntDistance> {
+    if group_size == 0 {
+        return fallible!(MakeMeasurement, "group_size must be positive")
+    }
+    let k = MO::Atom::exact_int_cast(group_size)?;
+    let PrivacyRelation { relation, forward_map, backward_map } = measurement.privacy_relation.clone();
+
+    Ok(Measurement::new(
+        measurement.input_domain.clone(),
+        measurement.output_domain.clone(),
+        measurement.function.clone(),
+        SymmetricDistance,
+        measurement.output_measure.clone(),
+        PrivacyRelation::new_all(
+            // relation
+            move |d_in: &IntDistance, d_out: &MO::Distance| relation(d_in, &MO::ungroup(d_out, k)),
+            // forward map
+            forward_map.map(|forward_map| move |d_in: &IntDistance|
+                Ok(Box::new(MO::group(&*forward_map(d_in)?, k)))),
+            // backward map
+            backward_map.map(|backward_map| move |d_out: &MO::Distance|
+                backward_map(&MO::ungroup(d_out, k))),
+        )))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::comb::make_advanced_composition;
+    use crate::dom::AllDomain;
+    use crate::error::ExplainUnwrap;
+    use crate::meas::make_base_laplace;
+    use crate::trans::make_count;
+
+    use super::*;
+
+    #[test]
+    fn test_make_group_privacy() -> Fallible<()> {
+        let count = (make_count::<i32, f64>()? >> make_base_laplace(1.)?)?;
+        let group = make_group_privacy(&count, 4)?;
+        group.invoke(&vec![1, 2, 3])?;
+
+        // one household of up to 4 individuals
+        assert!(group.check(&1, &4.)?);
+        assert!(!group.check(&1, &3.99)?);
+        assert_eq!(group.privacy_relation.forward_map.as_ref().unwrap_test()(&1)?, Box::new(4.));
+        assert!(make_group_privacy(&count, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_group_privacy_approximate() -> Fallible<()> {
+        let laplace = make_base_laplace::<AllDomain<f64>>(10.)?;
+        let composition = make_advanced_composition(&laplace, 10, 1e-6)?;
+        let count = (make_count::<i32, f64>()? >> composition)?;
+        let (epsilon, delta) = *count.privacy_relation.forward_map.as_ref().unwrap_test()(&1)?;
+
+        let group = make_group_privacy(&count, 2)?;
+        let (group_epsilon, group_delta) = *group.privacy_relation.forward_map.as_ref().unwrap_test()(&1)?;
+        assert_eq!(group_epsilon, 2. * epsilon);
+        assert_eq!(group_delta, 2. * epsilon.exp() * delta);
+        assert!(group.check(&1, &(group_epsilon, group_delta * 1.001))?);
+        assert!(!group.check(&1, &(group_epsilon, group_delta * 0.999))?);
+        Ok(())
+    }
+}
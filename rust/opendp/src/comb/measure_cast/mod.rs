@@ -0,0 +1,258 @@
+use std::fmt::Debug;
+
+use num::Float;
+
+use crate::core::{Domain, Measurement, Metric, PrivacyRelation};
+use crate::dist::{MaxDivergence, PrivacyLossDivergence, RenyiDivergence, SmoothedMaxDivergence, ZeroConcentratedDivergence};
+use crate::error::Fallible;
+use crate::traits::InfCast;
+
+/// Convert an epsilon-DP measurement into a rho-zCDP measurement, where rho = epsilon^2 / 2.
+pub fn make_pure_to_zcdp<DI, DO, MI, Q>(
+    measurement: &Measurement<DI, DO, MI, MaxDivergence<Q>>
+) -> Fallible<Measurement<DI, DO, MI, ZeroConcentratedDivergence<Q>>>
+    where DI: 'static + Domain,
+          DO: 'static + Domain,
+          MI: 'static + Metric,
+          Q: 'static + Float + InfCast<f64> {
+    let PrivacyRelation { relation, forward_map, backward_map } = measurement.privacy_relation.clone();
+
+    Ok(Measurement::new(
+        measurement.input_domain.clone(),
+        measurement.output_domain.clone(),
+        measurement.function.clone(),
+        measurement.input_metric.clone(),
+        ZeroConcentratedDivergence::default(),
+        PrivacyRelation::new_all(
+            // relation
+            move |d_in: &MI::Distance, &rho: &Q| {
+                if rho.is_sign_negative() {
+                    return fallible!(InvalidDistance, "rho must be non-negative")
+                }
+                relation(d_in, &rho_to_epsilon(rho)?)
+            },
+            // forward map
+            forward_map.map(|forward_map| move |d_in: &MI::Distance| {
+                let epsilon = *forward_map(d_in)?;
+                Ok(Box::new(epsilon.powi(2) / Q::inf_cast(2.)?))
+            }),
+            // backward map
+            backward_map.map(|backward_map| move |&rho: &Q| backward_map(&rho_to_epsilon(rho)?)),
+        )
+    ))
+}
+
+/// The largest epsilon whose zCDP conversion does not exceed `rho`.
+fn rho_to_epsilon<Q: Float + InfCast<f64>>(rho: Q) -> Fallible<Q> {
+    Ok((Q::inf_cast(2.)? * rho).sqrt())
+}
+
+/// Convert a rho-zCDP measurement into an (epsilon, delta)-DP measurement,
+/// where epsilon = rho + 2 * sqrt(rho * ln(1 / delta)).
+pub fn make_zcdp_to_approxdp<DI, DO, MI, Q>(
+    measurement: &Measurement<DI, DO, MI, ZeroConcentratedDivergence<Q>>
+) -> Fallible<Measurement<DI, DO, MI, SmoothedMaxDivergence<Q>>>
+    where DI: 'static + Domain,
+          DO: 'static + Domain,
+          MI: 'static + Metric,
+          Q: 'static + Float {
+    let PrivacyRelation { relation, backward_map, .. } = measurement.privacy_relation.clone();
+
+    Ok(Measurement::new(
+        measurement.input_domain.clone(),
+        measurement.output_domain.clone(),
+        measurement.function.clone(),
+        measurement.input_metric.clone(),
+        SmoothedMaxDivergence::default(),
+        PrivacyRelation::new_all(
+            // relation
+            move |d_in: &MI::Distance, &(epsilon, delta): &(Q, Q)| {
+                relation(d_in, &approxdp_to_rho(epsilon, delta)?)
+            },
+            // forward map: there are many (epsilon, delta) pairs for each rho
+            None::<fn(&_) -> _>,
+            // backward map
+            backward_map.map(|backward_map| move |&(epsilon, delta): &(Q, Q)|
+                backward_map(&approxdp_to_rho(epsilon, delta)?)),
+        )
+    ))
+}
+
+/// The largest rho whose (epsilon, delta)-DP conversion does not exceed (`epsilon`, `delta`).
+///
+/// Solves epsilon = rho + 2 * sqrt(rho * ln(1 / delta)) for rho.
+fn approxdp_to_rho<Q: Float>(epsilon: Q, delta: Q) -> Fallible<Q> {
+    if epsilon.is_sign_negative() {
+        return fallible!(InvalidDistance, "epsilon must be non-negative")
+    }
+    if delta.is_sign_negative() {
+        return fallible!(InvalidDistance, "delta must be non-negative")
+    }
+    if delta.is_zero() {
+        return Ok(Q::zero())
+    }
+    let log_term = delta.recip().ln().max(Q::zero());
+    Ok(((log_term + epsilon).sqrt() - log_term.sqrt()).powi(2))
+}
+
+/// Convert a Rényi DP measurement into an (epsilon, delta)-DP measurement,
+/// where epsilon = min over alpha of epsilon(alpha) + ln(1 / delta) / (alpha - 1).
+pub fn make_rdp_to_approxdp<DI, DO, MI, Q>(
+    measurement: &Measurement<DI, DO, MI, RenyiDivergence<Q>>
+) -> Fallible<Measurement<DI, DO, MI, SmoothedMaxDivergence<Q>>>
+    where DI: 'static + Domain,
+          DO: 'static + Domain,
+          MI: 'static + Metric,
+          MI::Distance: PartialOrd,
+          Q: 'static + Float + Debug {
+    let PrivacyRelation { relation, backward_map, .. } = measurement.privacy_relation.clone();
+    let alphas = measurement.output_measure.alphas().to_vec();
+
+    Ok(Measurement::new(
+        measurement.input_domain.clone(),
+        measurement.output_domain.clone(),
+        measurement.function.clone(),
+        measurement.input_metric.clone(),
+        SmoothedMaxDivergence::default(),
+        PrivacyRelation::new_all(
+            // relation: private if the curve at any one order converts to (epsilon, delta)
+            enclose!(alphas, move |d_in: &MI::Distance, &(epsilon, delta): &(Q, Q)| {
+                for curve in approxdp_to_curves(&alphas, epsilon, delta)? {
+                    if relation(d_in, &curve)? {
+                        return Ok(true)
+                    }
+                }
+                Ok(false)
+            }),
+            // forward map: there are many (epsilon, delta) pairs for each curve
+            None::<fn(&_) -> _>,
+            // backward map: the largest d_in over all orders
+            backward_map.map(|backward_map| move |&(epsilon, delta): &(Q, Q)| {
+                approxdp_to_curves(&alphas, epsilon, delta)?.iter()
+                    .try_fold(None, |d_in: Option<Box<MI::Distance>>, curve| -> Fallible<_> {
+                        let d_in_alpha = backward_map(curve)?;
+                        Ok(match d_in {
+                            Some(d_in) if *d_in >= *d_in_alpha => Some(d_in),
+                            _ => Some(d_in_alpha)
+                        })
+                    })?
+                    .ok_or_else(|| err!(FailedRelation, "(epsilon, delta) is not reachable at any order"))
+            }),
+        )
+    ))
+}
+
+/// Convert a measurement whose privacy is expressed by its privacy loss distribution into an (epsilon, delta)-DP measurement,
+/// where delta is the tight delta at epsilon of the distribution.
+pub fn make_pld_to_approxdp<DI, DO, MI>(
+    measurement: &Measurement<DI, DO, MI, PrivacyLossDivergence>
+) -> Fallible<Measurement<DI, DO, MI, SmoothedMaxDivergence<f64>>>
+    where DI: 'static + Domain,
+          DO: 'static + Domain,
+          MI: 'static + Metric {
+    let pld = measurement.privacy_relation.forward_map.clone()
+        .ok_or_else(|| err!(MakeMeasurement, "the measurement must map d_in to its privacy loss distribution"))?;
+
+    Ok(Measurement::new(
+        measurement.input_domain.clone(),
+        measurement.output_domain.clone(),
+        measurement.function.clone(),
+        measurement.input_metric.clone(),
+        SmoothedMaxDivergence::default(),
+        PrivacyRelation::new_fallible(move |d_in: &MI::Distance, &(epsilon, delta): &(f64, f64)| {
+            if epsilon.is_sign_negative() {
+                return fallible!(InvalidDistance, "epsilon must be non-negative")
+            }
+            if delta.is_sign_negative() {
+                return fallible!(InvalidDistance, "delta must be non-negative")
+            }
+            Ok(pld(d_in)?.delta(epsilon)? <= delta)
+        })
+    ))
+}
+
+/// For each order alpha at which (`epsilon`, `delta`) is reachable, a curve that only constrains alpha.
+fn approxdp_to_curves<Q: Float>(alphas: &[Q], epsilon: Q, delta: Q) -> Fallible<Vec<Vec<Q>>> {
+    if epsilon.is_sign_negative() {
+        return fallible!(InvalidDistance, "epsilon must be non-negative")
+    }
+    if delta.is_sign_negative() {
+        return fallible!(InvalidDistance, "delta must be non-negative")
+    }
+    let log_term = delta.recip().ln();
+    Ok(alphas.iter().enumerate()
+        .map(|(i, &alpha)| (i, epsilon - log_term / (alpha - Q::one())))
+        .filter(|(_, epsilon_alpha)| !epsilon_alpha.is_sign_negative())
+        .map(|(i, epsilon_alpha)| {
+            let mut curve = vec![Q::infinity(); alphas.len()];
+            curve[i] = epsilon_alpha;
+            curve
+        })
+        .collect())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::comb::make_basic_composition;
+    use crate::dom::AllDomain;
+    use crate::error::ExplainUnwrap;
+    use crate::meas::{make_base_gaussian_rdp, make_base_gaussian_zcdp, make_base_laplace};
+
+    use super::*;
+
+    #[test]
+    fn test_make_pure_to_zcdp() -> Fallible<()> {
+        let laplace = make_base_laplace::<AllDomain<f64>>(1.)?;
+        let measurement = make_pure_to_zcdp(&laplace)?;
+        assert!(measurement.check(&1., &0.5)?);
+        assert!(!measurement.check(&1., &0.49)?);
+        assert_eq!(measurement.privacy_relation.forward_map.as_ref().unwrap_test()(&2.)?, Box::new(2.));
+        assert!((*measurement.privacy_relation.backward_map.as_ref().unwrap_test()(&2.)? - 2.).abs() < 1e-12);
+
+        // the converted measurement can now be composed with a gaussian
+        let gaussian = make_base_gaussian_zcdp::<AllDomain<f64>>(1.)?;
+        let composition = make_basic_composition(vec![&measurement, &gaussian])?;
+        assert!(composition.check(&1., &1.)?);
+        assert!(!composition.check(&1., &0.99)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_zcdp_to_approxdp() -> Fallible<()> {
+        let gaussian = make_base_gaussian_zcdp::<AllDomain<f64>>(1.)?;
+        let measurement = make_zcdp_to_approxdp(&gaussian)?;
+        // rho = 0.5, so epsilon = 0.5 + 2 * sqrt(0.5 * ln(1e6)) ~= 5.7566
+        assert!(measurement.check(&1., &(5.76, 1e-6))?);
+        assert!(!measurement.check(&1., &(5.75, 1e-6))?);
+        assert!(!measurement.check(&1., &(100., 0.))?);
+        assert!(measurement.check(&0., &(0., 0.))?);
+        assert!(measurement.privacy_relation.forward_map.is_none());
+
+        let backward_map = measurement.privacy_relation.backward_map.as_ref().unwrap_test();
+        assert!((*backward_map(&(5.7566, 1e-6))? - 1.).abs() < 1e-4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_rdp_to_approxdp() -> Fallible<()> {
+        let gaussian = make_base_gaussian_rdp::<AllDomain<f64>>(1., vec![2., 10., 20.])?;
+        let measurement = make_rdp_to_approxdp(&gaussian)?;
+        // epsilon(alpha) = alpha / 2, so at alpha = 10, epsilon = 5 + ln(1e6) / 9 ~= 6.535
+        assert!(measurement.check(&1., &(6.54, 1e-6))?);
+        assert!(!measurement.check(&1., &(6.53, 1e-6))?);
+        assert!(!measurement.check(&1., &(100., 0.))?);
+
+        let backward_map = measurement.privacy_relation.backward_map.as_ref().unwrap_test();
+        assert!((*backward_map(&(6.5351, 1e-6))? - 1.).abs() < 1e-4);
+        assert!(backward_map(&(0.1, 1e-6)).is_err());
+
+        // the curves of repeated compositions add up at each order
+        let gaussians = (0..11)
+            .map(|_| make_base_gaussian_rdp::<AllDomain<f64>>(1., vec![2., 10., 20.]))
+            .collect::<Fallible<Vec<_>>>()?;
+        let composition = make_basic_composition(gaussians.iter().collect())?;
+        assert_eq!(composition.privacy_relation.forward_map.as_ref().unwrap_test()(&1.)?, Box::new(vec![11., 55., 110.]));
+        Ok(())
+    }
+}
@@ -1,5 +1,30 @@
+#[cfg(feature="contrib")]
+pub mod advanced_composition;
+#[cfg(feature="contrib")]
+pub use crate::comb::advanced_composition::*;
+
+#[cfg(feature="contrib")]
+pub mod amplification;
+#[cfg(feature="contrib")]
+pub use crate::comb::amplification::*;
 
 #[cfg(feature="contrib")]
 pub mod chain;
 #[cfg(feature="contrib")]
-pub use crate::comb::chain::*;
\ No newline at end of file
+pub use crate::comb::chain::*;
+#[cfg(feature="contrib")]
+pub mod group_privacy;
+#[cfg(feature="contrib")]
+pub use crate::comb::group_privacy::*;
+#[cfg(feature="contrib")]
+pub mod measure_cast;
+#[cfg(feature="contrib")]
+pub use crate::comb::measure_cast::*;
+#[cfg(feature="contrib")]
+pub mod parallel_composition;
+#[cfg(feature="contrib")]
+pub use crate::comb::parallel_composition::*;
+#[cfg(feature="contrib")]
+pub mod pld;
+#[cfg(feature="contrib")]
+pub use crate::comb::pld::*;
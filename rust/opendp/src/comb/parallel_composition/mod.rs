@@ -0,0 +1,139 @@
+use crate::comb::BasicCompositionMeasure;
+use crate::core::{Domain, Function, Measurement, PrivacyRelation};
+use crate::dist::{IntDistance, PartitionDistance, SymmetricDistance};
+use crate::dom::VectorDomain;
+use crate::error::Fallible;
+use crate::traits::MeasureDistance;
+
+/// Construct the parallel composition of `measurements`, where the ith measurement is applied to the ith partition
+/// of a partitioned dataset, like the output of `make_partition_by`. The output is a vector, with one release per partition.
+///
+/// Only the partitions that differ incur privacy loss, so if d_in = (d_partition, num_changed),
+/// the composition is charged for the `num_changed` largest privacy losses at d_partition, instead of all of them.
+pub fn make_parallel_composition<DI, DO, MO>(
+    measurements: Vec<&Measurement<DI, DO, SymmetricDistance, MO>>
+) -> Fallible<Measurement<VectorDomain<DI>, VectorDomain<DO>, PartitionDistance, MO>>
+    where DI: 'static + Domain,
+          DO: 'static + Domain,
+          MO: 'static + BasicCompositionMeasure,
+          MO::Distance: Clone + MeasureDistance {
+    let first = measurements.first()
+        .ok_or_else(|| err!(MakeMeasurement, "Must have at least one measurement"))?;
+
+    if measurements.iter().any(|m| m.input_domain != first.input_domain) {
+        return fallible!(DomainMismatch, "Input domain mismatch");
+    } else if measurements.iter().any(|m| m.output_domain != first.output_domain) {
+        return fallible!(DomainMismatch, "Output domain mismatch");
+    } else if measurements.iter().any(|m| m.output_measure != first.output_measure) {
+        return fallible!(MeasureMismatch, "Output measure mismatch");
+    }
+
+    let functions = measurements.iter().map(|m| m.function.clone()).collect::<Vec<_>>();
+    let relations = measurements.iter().map(|m| m.privacy_relation.clone()).collect::<Vec<_>>();
+    let forward_maps = relations.iter()
+        .map(|relation| relation.forward_map.clone())
+        .collect::<Option<Vec<_>>>();
+    let output_measure = first.output_measure.clone();
+
+    Ok(Measurement::new(
+        VectorDomain::new(first.input_domain.clone()),
+        VectorDomain::new(first.output_domain.clone()),
+        Function::new_fallible(move |arg: &Vec<DI::Carrier>| {
+            if arg.len() != functions.len() {
+                return fallible!(FailedFunction, "there must be one partition per measurement")
+            }
+            functions.iter().zip(arg.iter()).map(|(f, partition)| f.eval(partition)).collect()
+        }),
+        PartitionDistance,
+        first.output_measure.clone(),
+        PrivacyRelation::new_all(
+            // relation: each changed partition may spend an even share of d_out
+            enclose!((relations, output_measure), move |&(d_partition, num_changed): &(IntDistance, IntDistance), d_out: &MO::Distance| {
+                if num_changed == 0 {
+                    return Ok(true)
+                }
+                let num_changed = (num_changed as usize).min(relations.len());
+                let share = match output_measure.split(d_out, vec![None; num_changed])? {
+                    Some(mut shares) => shares.remove(0),
+                    None => return Ok(false)
+                };
+                for relation in relations.iter() {
+                    if !relation.eval(&d_partition, &share)? {
+                        return Ok(false)
+                    }
+                }
+                Ok(true)
+            }),
+            // forward map: the largest privacy loss of any partition (in each component), once per changed partition
+            forward_maps.map(|forward_maps| move |&(d_partition, num_changed): &(IntDistance, IntDistance)| {
+                let d_i = forward_maps.iter()
+                    .map(|forward_map| forward_map(&d_partition).map(|d| *d))
+                    .collect::<Fallible<Vec<_>>>()?;
+                let num_changed = (num_changed as usize).min(d_i.len());
+                let d_max = output_measure.max(d_i)?;
+                output_measure.compose(vec![d_max; num_changed]).map(Box::new)
+            }),
+            None::<fn(&_) -> _>)
+    ))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::dist::{MaxDivergence, SmoothedMaxDivergence};
+    use crate::dom::AllDomain;
+    use crate::error::ExplainUnwrap;
+    use crate::meas::make_base_geometric;
+    use crate::trans::{make_count, make_partition_by};
+
+    use super::*;
+
+    #[test]
+    fn test_make_parallel_composition() -> Fallible<()> {
+        let counts = (0..3)
+            .map(|_| make_count::<i32, i32>()? >> make_base_geometric(1., Some((0, 10)))?)
+            .collect::<Fallible<Vec<_>>>()?;
+        let parallel = make_parallel_composition(counts.iter().collect())?;
+        let measurement = (make_partition_by::<char, i32>(vec!['A', 'B', 'C'])? >> parallel)?;
+
+        let ret = measurement.invoke(&vec![('A', 1), ('C', 2), ('A', 3)])?;
+        assert_eq!(ret.len(), 3);
+
+        // one record changes one partition, so only one count is charged
+        assert!(measurement.check(&1, &1.)?);
+        assert!(!measurement.check(&1, &0.99)?);
+        // two records may change two partitions by two records each
+        assert!(measurement.check(&2, &4.)?);
+        assert!(!measurement.check(&2, &3.99)?);
+        assert_eq!(measurement.privacy_relation.forward_map.as_ref().unwrap_test()(&2)?, Box::new(4.));
+        // no more partitions can change than there are measurements
+        let parallel = make_parallel_composition(counts.iter().collect())?;
+        assert!(parallel.check(&(1, IntDistance::MAX), &3.)?);
+
+        assert!(make_parallel_composition::<AllDomain<i32>, AllDomain<i32>, MaxDivergence<f64>>(vec![]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_parallel_composition_approximate() -> Fallible<()> {
+        let make_dummy_meas = |(epsilon, delta): (f64, f64)| Measurement::new(
+            VectorDomain::new(AllDomain::<i32>::new()),
+            AllDomain::<i32>::new(),
+            Function::new(|a: &Vec<i32>| a.len() as i32),
+            SymmetricDistance,
+            SmoothedMaxDivergence::default(),
+            PrivacyRelation::new_all(
+                move |d_in: &IntDistance, d_out: &(f64, f64)| Ok(d_out.0 >= epsilon * *d_in as f64 && d_out.1 >= delta * *d_in as f64),
+                Some(move |d_in: &IntDistance| Ok(Box::new((epsilon * *d_in as f64, delta * *d_in as f64)))),
+                None::<fn(&_) -> _>));
+        // one partition has the larger epsilon, the other has the larger delta
+        let measurements = [make_dummy_meas((1., 1e-7)), make_dummy_meas((0.5, 1e-6))];
+        let parallel = make_parallel_composition(measurements.iter().collect())?;
+
+        assert_eq!(parallel.privacy_relation.forward_map.as_ref().unwrap_test()(&(1, 1))?, Box::new((1., 1e-6)));
+        assert_eq!(parallel.privacy_relation.forward_map.as_ref().unwrap_test()(&(1, 2))?, Box::new((2., 2e-6)));
+        assert!(parallel.check(&(1, 1), &(1., 1e-6))?);
+        assert!(!parallel.check(&(1, 1), &(1., 1e-7))?);
+        Ok(())
+    }
+}
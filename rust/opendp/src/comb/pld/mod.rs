@@ -0,0 +1,97 @@
+use crate::core::{Domain, Function, Measurement, Metric};
+use crate::dist::PrivacyLossDivergence;
+use crate::dom::VectorDomain;
+use crate::error::Fallible;
+use crate::pld::make_pld_privacy_relation;
+
+/// Compose `measurements` under a privacy loss distribution (PLD) accountant.
+///
+/// Each measurement must carry its PLD in the forward map of its privacy relation,
+/// as the measurements made by the `make_base_*_pld` constructors do.
+/// The PLD of the composition is the convolution of the PLDs of its members, so it is tight up to their discretization.
+/// The output is a vector, with one release per measurement.
+pub fn make_pld_composition<DI, DO, MI>(
+    measurements: Vec<&Measurement<DI, DO, MI, PrivacyLossDivergence>>
+) -> Fallible<Measurement<DI, VectorDomain<DO>, MI, PrivacyLossDivergence>>
+    where DI: 'static + Domain,
+          DO: 'static + Domain,
+          MI: 'static + Metric {
+    let first = measurements.first()
+        .ok_or_else(|| err!(MakeMeasurement, "Must have at least one measurement"))?;
+
+    if measurements.iter().any(|m| m.input_domain != first.input_domain) {
+        return fallible!(DomainMismatch, "Input domain mismatch");
+    } else if measurements.iter().any(|m| m.output_domain != first.output_domain) {
+        return fallible!(DomainMismatch, "Output domain mismatch");
+    } else if measurements.iter().any(|m| m.input_metric != first.input_metric) {
+        return fallible!(MetricMismatch, "Input metric mismatch");
+    }
+
+    let plds = measurements.iter()
+        .map(|m| m.privacy_relation.forward_map.clone())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| err!(MakeMeasurement, "each measurement must map d_in to its privacy loss distribution"))?;
+    let functions = measurements.iter()
+        .map(|m| m.function.clone())
+        .collect::<Vec<_>>();
+
+    Ok(Measurement::new(
+        first.input_domain.clone(),
+        VectorDomain::new(first.output_domain.clone()),
+        Function::new_fallible(move |arg: &DI::Carrier|
+            functions.iter().map(|f| f.eval(arg)).collect()),
+        first.input_metric.clone(),
+        PrivacyLossDivergence,
+        make_pld_privacy_relation(move |d_in: &MI::Distance| {
+            let (head, tail) = plds.split_first()
+                .ok_or_else(|| err!(FailedRelation, "there must be at least one privacy loss distribution"))?;
+            tail.iter().try_fold(*head(d_in)?, |pld, map| pld.compose(&*map(d_in)?))
+        }),
+    ))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::comb::make_pld_to_approxdp;
+    use crate::dist::AbsoluteDistance;
+    use crate::dom::AllDomain;
+    use crate::meas::{make_base_gaussian_pld, make_base_laplace_pld};
+    use crate::pld::PrivacyLossDistribution;
+
+    use super::*;
+
+    #[test]
+    fn test_make_pld_composition() -> Fallible<()> {
+        let measurement = make_base_gaussian_pld::<AllDomain<f64>>(2., 1e-3)?;
+        let composition = make_pld_composition(vec![&measurement; 4])?;
+        assert_eq!(composition.invoke(&0.)?.len(), 4);
+
+        // equivalent to one gaussian release at scale 1
+        let pld = PrivacyLossDistribution::new_gaussian(2., 1., 1e-3)?;
+        let composed = pld.compose(&pld)?.compose(&pld)?.compose(&pld)?;
+        assert!(composition.check(&1., &composed)?);
+        assert!(!composition.check(&1., &pld)?);
+
+        // delta(1) of the gaussian mechanism at scale 1 is about 0.1269
+        let composition = make_pld_to_approxdp(&composition)?;
+        assert!(composition.check(&1., &(1., 0.128))?);
+        assert!(!composition.check(&1., &(1., 0.126))?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_pld_composition_mismatch() -> Fallible<()> {
+        let laplace = make_base_laplace_pld(1., 1e-3)?;
+        let gaussian = make_base_gaussian_pld::<AllDomain<f64>>(1., 1e-3)?;
+        let composition = make_pld_composition(vec![&laplace, &gaussian])?;
+        assert_eq!(composition.invoke(&0.)?.len(), 2);
+        assert!(make_pld_composition::<AllDomain<f64>, AllDomain<f64>, AbsoluteDistance<f64>>(vec![]).is_err());
+
+        // the discretizations must match to convolve the distributions
+        let coarse = make_base_laplace_pld(1., 1e-2)?;
+        let composition = make_pld_composition(vec![&laplace, &coarse])?;
+        assert!(composition.check(&1., &PrivacyLossDistribution::new_laplace(0.5, 1., 1e-3)?).is_err());
+        Ok(())
+    }
+}
@@ -20,12 +20,14 @@
 // Ordering of generic arguments
 // DI, DO, MI, MO, TI, TO, QI, QO
 
+use std::any::{Any, TypeId};
 use std::rc::Rc;
 
 use crate::dom::PairDomain;
 use crate::error::*;
 use crate::traits::{DistanceConstant, InfCast};
 use crate::dist::IntDistance;
+use crate::search::{bisect_min, Bisect};
 use std::fmt::Debug;
 
 /// A set which constrains the input or output of a [`Function`].
@@ -39,11 +41,17 @@ pub trait Domain: Clone + PartialEq + Debug {
 }
 
 /// A mathematical function which maps values from an input [`Domain`] to an output [`Domain`].
-#[derive(Clone)]
 pub struct Function<DI: Domain, DO: Domain> {
     pub function: Rc<dyn Fn(&DI::Carrier) -> Fallible<DO::Carrier>>,
 }
 
+// Auto-deriving Clone would put trait bounds on the carrier types, so we implement it manually.
+impl<DI: Domain, DO: Domain> Clone for Function<DI, DO> {
+    fn clone(&self) -> Self {
+        Function { function: self.function.clone() }
+    }
+}
+
 impl<DI: Domain, DO: Domain> Function<DI, DO> {
     pub fn new(function: impl Fn(&DI::Carrier) -> DO::Carrier + 'static) -> Self {
         Self::new_fallible(move |arg| Ok(function(arg)))
@@ -125,31 +133,49 @@ impl<MI: Metric, MO: Metric, MX: Metric> HintTt<MI, MO, MX> {
 ///
 /// A `PrivacyRelation` is implemented as a function that takes an input [`Metric::Distance`] and output [`Measure::Distance`],
 /// and returns a boolean indicating if the relation holds.
-#[derive(Clone)]
+///
+/// The optional `forward_map` computes the smallest output distance for which the relation holds,
+/// which is what composition combinators use to total the privacy loss of their members.
 pub struct PrivacyRelation<MI: Metric, MO: Measure> {
     pub relation: Rc<dyn Fn(&MI::Distance, &MO::Distance) -> Fallible<bool>>,
+    pub forward_map: Option<Rc<dyn Fn(&MI::Distance) -> Fallible<Box<MO::Distance>>>>,
     pub backward_map: Option<Rc<dyn Fn(&MO::Distance) -> Fallible<Box<MI::Distance>>>>,
 }
 
+// Auto-deriving Clone would put trait bounds on the distance types, so we implement it manually.
+impl<MI: Metric, MO: Measure> Clone for PrivacyRelation<MI, MO> {
+    fn clone(&self) -> Self {
+        PrivacyRelation {
+            relation: self.relation.clone(),
+            forward_map: self.forward_map.clone(),
+            backward_map: self.backward_map.clone(),
+        }
+    }
+}
+
 impl<MI: Metric, MO: Measure> PrivacyRelation<MI, MO> {
     pub fn new(relation: impl Fn(&MI::Distance, &MO::Distance) -> bool + 'static) -> Self {
         PrivacyRelation {
             relation: Rc::new(move |d_in: &MI::Distance, d_out: &MO::Distance| Ok(relation(d_in, d_out))),
+            forward_map: None,
             backward_map: None,
         }
     }
     pub fn new_fallible(relation: impl Fn(&MI::Distance, &MO::Distance) -> Fallible<bool> + 'static) -> Self {
         PrivacyRelation {
             relation: Rc::new(relation),
+            forward_map: None,
             backward_map: None,
         }
     }
     pub fn new_all(
         relation: impl Fn(&MI::Distance, &MO::Distance) -> Fallible<bool> + 'static,
+        forward_map: Option<impl Fn(&MI::Distance) -> Fallible<Box<MO::Distance>> + 'static>,
         backward_map: Option<impl Fn(&MO::Distance) -> Fallible<Box<MI::Distance>> + 'static>,
     ) -> Self {
         PrivacyRelation {
             relation: Rc::new(relation),
+            forward_map: forward_map.map(|h| Rc::new(h) as Rc<_>),
             backward_map: backward_map.map(|h| Rc::new(h) as Rc<_>),
         }
     }
@@ -157,8 +183,13 @@ impl<MI: Metric, MO: Measure> PrivacyRelation<MI, MO> {
         MI::Distance: InfCast<MO::Distance> + Clone,
         MO::Distance: DistanceConstant<MI::Distance> {
         PrivacyRelation::new_all(
+            // relation
             enclose!(c, move |d_in: &MI::Distance, d_out: &MO::Distance|
                 Ok(d_out.clone() >= MO::Distance::inf_cast(d_in.clone())? * c.clone())),
+            // forward map
+            Some(enclose!(c, move |d_in: &MI::Distance|
+                Ok(Box::new(MO::Distance::inf_cast(d_in.clone())? * c.clone())))),
+            // backward map
             Some(enclose!(c, move |d_out: &MO::Distance|
                 Ok(Box::new(MI::Distance::inf_cast(d_out.clone() / c.clone())?)))))
     }
@@ -178,6 +209,36 @@ fn chain_option_maps<QI, QX, QO>(
     }
 }
 
+type HintFn<QI, QO, QX> = Rc<dyn Fn(&QI, &QO) -> Fallible<Box<QX>>>;
+
+fn make_bisect_hint_typed<QI: 'static, QO: 'static, QX: 'static, T: Bisect>(
+    relation0: Rc<dyn Fn(&QI, &QX) -> Fallible<bool>>
+) -> HintFn<QI, QO, QX> {
+    Rc::new(move |d_in: &QI, _d_out: &QO| {
+        // a relation that fails at some d_mid is treated as not holding there
+        let d_mid = bisect_min(|d_mid: &T| (d_mid as &dyn Any).downcast_ref::<QX>()
+            .is_some_and(|d_mid| relation0(d_in, d_mid).unwrap_or(false)))
+            .ok_or_else(|| err!(FailedRelation, "no intermediate distance satisfies the inner relation"))?;
+        (Box::new(d_mid) as Box<dyn Any>).downcast::<QX>()
+            .map_err(|_| err!(FailedCast, "failed to cast the intermediate distance"))
+    })
+}
+
+/// Makes a hint that binary searches for the tightest intermediate distance d_mid, the smallest d_mid that satisfies `relation0`.
+/// Since `relation1` is monotone, any larger d_mid would only make the chained relation more conservative.
+/// Returns None if the intermediate distance type cannot be searched.
+fn make_bisect_hint<QI: 'static, QO: 'static, QX: 'static>(
+    relation0: &Rc<dyn Fn(&QI, &QX) -> Fallible<bool>>
+) -> Option<HintFn<QI, QO, QX>> {
+    macro_rules! dispatch_bisect {
+        ($($ty:ty),+) => ($(if TypeId::of::<QX>() == TypeId::of::<$ty>() {
+            return Some(make_bisect_hint_typed::<QI, QO, QX, $ty>(relation0.clone()))
+        })+)
+    }
+    dispatch_bisect!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+    None
+}
+
 impl<MI: 'static + Metric, MO: 'static + Measure> PrivacyRelation<MI, MO> {
     pub fn make_chain<MX: 'static + Metric>(
         relation1: &PrivacyRelation<MX, MO>,
@@ -200,25 +261,26 @@ impl<MI: 'static + Metric, MO: 'static + Measure> PrivacyRelation<MI, MO> {
         } else if let Some(backward_map) = &relation1.backward_map {
             Some(HintMt::new(enclose!(backward_map, move |_d_in, d_out| backward_map(d_out))))
         } else {
-            None
+            make_bisect_hint(&relation0.relation).map(|hint| HintMt { hint })
         };
         if let Some(hint) = hint {
             Self::make_chain_hint(relation1, relation0, &hint)
         } else {
-            // TODO: Implement binary search for hints.
-            unimplemented!("Binary search for hints not implemented, must have maps or supply explicit hint.")
+            PrivacyRelation::new_fallible(|_d_in, _d_out|
+                fallible!(FailedRelation, "the intermediate distance cannot be searched, so the relations must have maps or an explicit hint"))
         }
     }
 
     fn make_chain_hint<MX: 'static + Metric>(relation1: &PrivacyRelation<MX, MO>, relation0: &StabilityRelation<MI, MX>, hint: &HintMt<MI, MO, MX>) -> Self {
         let PrivacyRelation {
             relation: relation1,
+            forward_map: forward_map1,
             backward_map: backward_map1
         } = relation1;
 
         let StabilityRelation {
             relation: relation0,
-            forward_map: _,
+            forward_map: forward_map0,
             backward_map: backward_map0,
         } = relation0;
 
@@ -229,6 +291,7 @@ impl<MI: 'static + Metric, MO: 'static + Measure> PrivacyRelation<MI, MO> {
                 let d_mid = h(d_in, d_out)?;
                 Ok(relation0(d_in, &d_mid)? && relation1(&d_mid, d_out)?)
             }),
+            chain_option_maps(forward_map1, forward_map0),
             chain_option_maps(backward_map0, backward_map1))
     }
 }
@@ -302,13 +365,13 @@ impl<MI: 'static + Metric, MO: 'static + Metric> StabilityRelation<MI, MO> {
             let backward_map = backward_map.clone();
             Some(HintTt::new_fallible(move |_d_in, d_out| backward_map(d_out)))
         } else {
-            None
+            make_bisect_hint(&relation0.relation).map(|hint| HintTt { hint })
         };
         if let Some(hint) = hint {
             Self::make_chain_hint(relation1, relation0, &hint)
         } else {
-            // TODO: Implement binary search for hints.
-            unimplemented!("Binary search for hints not implemented, must have maps or supply explicit hint.")
+            StabilityRelation::new_fallible(|_d_in, _d_out|
+                fallible!(FailedRelation, "the intermediate distance cannot be searched, so the relations must have maps or an explicit hint"))
         }
     }
 
@@ -416,7 +479,7 @@ impl<DI: Domain, DO: Domain, MI: Metric, MO: Metric> Transformation<DI, DO, MI,
 
 #[cfg(test)]
 mod tests {
-    use crate::dist::L1Distance;
+    use crate::dist::{L1Distance, MaxDivergence};
     use crate::dom::AllDomain;
     use crate::error::ExplainUnwrap;
 
@@ -435,4 +498,25 @@ mod tests {
         let ret = identity.invoke(&arg).unwrap_test();
         assert_eq!(ret, 99);
     }
+
+    #[test]
+    fn test_make_chain_bisect() -> Fallible<()> {
+        // relations without maps, so the intermediate distance must be searched
+        let relation0 = StabilityRelation::<L1Distance<i32>, L1Distance<f64>>::new(|d_in, d_mid| *d_mid >= *d_in as f64 * 2.5);
+        let relation1 = PrivacyRelation::<L1Distance<f64>, MaxDivergence<f64>>::new(|d_mid, d_out| *d_out >= d_mid / 10.);
+        let chain = PrivacyRelation::make_chain(&relation1, &relation0, None);
+        assert!(chain.eval(&2, &0.5)?);
+        assert!(!chain.eval(&2, &0.49)?);
+
+        let relation1 = StabilityRelation::<L1Distance<f64>, L1Distance<i32>>::new(|d_mid, d_out| *d_out as f64 >= d_mid * 2.);
+        let chain = StabilityRelation::make_chain(&relation1, &relation0, None);
+        assert!(chain.eval(&2, &10)?);
+        assert!(!chain.eval(&2, &9)?);
+
+        // no intermediate distance satisfies the inner relation
+        let relation0 = StabilityRelation::<L1Distance<i32>, L1Distance<u8>>::new(|d_in, d_mid| *d_mid as i32 >= d_in * 1000);
+        let relation1 = StabilityRelation::<L1Distance<u8>, L1Distance<u8>>::new(|d_mid, d_out| d_out >= d_mid);
+        assert!(StabilityRelation::make_chain(&relation1, &relation0, None).eval(&1, &255).is_err());
+        Ok(())
+    }
 }
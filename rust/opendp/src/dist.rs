@@ -2,7 +2,12 @@
 
 use std::marker::PhantomData;
 
+use num::Float;
+
 use crate::core::{DatasetMetric, Measure, Metric, SensitivityMetric};
+use crate::error::Fallible;
+#[cfg(feature="contrib")]
+use crate::pld::PrivacyLossDistribution;
 use std::fmt::{Debug, Formatter};
 
 // default type for distances between datasets
@@ -48,6 +53,101 @@ impl<Q: Clone> Measure for SmoothedMaxDivergence<Q> {
     type Distance = (Q, Q);
 }
 
+/// Zero-concentrated differential privacy (zCDP). The distance is rho.
+#[derive(Clone)]
+pub struct ZeroConcentratedDivergence<Q>(PhantomData<Q>);
+
+impl<Q> Default for ZeroConcentratedDivergence<Q> {
+    fn default() -> Self { ZeroConcentratedDivergence(PhantomData) }
+}
+
+impl<Q> PartialEq for ZeroConcentratedDivergence<Q> {
+    fn eq(&self, _other: &Self) -> bool { true }
+}
+impl<Q> Debug for ZeroConcentratedDivergence<Q> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "ZeroConcentratedDivergence()")
+    }
+}
+impl<Q: Clone> Measure for ZeroConcentratedDivergence<Q> {
+    type Distance = Q;
+}
+
+/// Rényi differential privacy, at each of a fixed set of orders alpha.
+/// The distance is a curve: the epsilon at each order, listed in the same order as the alphas.
+#[derive(Clone, PartialEq)]
+pub struct RenyiDivergence<Q> {
+    alphas: Vec<Q>
+}
+
+impl<Q: Float> RenyiDivergence<Q> {
+    pub fn new(alphas: Vec<Q>) -> Fallible<Self> {
+        if alphas.is_empty() {
+            return fallible!(MakeMeasurement, "there must be at least one alpha")
+        }
+        if alphas.iter().any(|alpha| !alpha.is_finite() || *alpha <= Q::one()) {
+            return fallible!(MakeMeasurement, "alphas must be finite and greater than one")
+        }
+        if alphas.iter().enumerate().any(|(i, alpha)| alphas[..i].contains(alpha)) {
+            return fallible!(MakeMeasurement, "alphas must be distinct")
+        }
+        Ok(RenyiDivergence { alphas })
+    }
+}
+
+impl<Q> RenyiDivergence<Q> {
+    pub fn alphas(&self) -> &[Q] { &self.alphas }
+
+    /// Check that a curve has one epsilon per order.
+    pub fn check_curve(&self, curve: &[Q]) -> Fallible<()> {
+        if curve.len() != self.alphas.len() {
+            return fallible!(InvalidDistance, "expected a curve with {} epsilons, got {}", self.alphas.len(), curve.len())
+        }
+        Ok(())
+    }
+}
+
+/// A placeholder with no orders, required by the `Measure: Default` bound.
+/// It never compares equal to a measure made by [`RenyiDivergence::new`], which always has at least one order.
+impl<Q> Default for RenyiDivergence<Q> {
+    fn default() -> Self { RenyiDivergence { alphas: Vec::new() } }
+}
+
+impl<Q: Debug> Debug for RenyiDivergence<Q> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "RenyiDivergence({:?})", self.alphas)
+    }
+}
+impl<Q: Clone + PartialEq + Debug> Measure for RenyiDivergence<Q> {
+    type Distance = Vec<Q>;
+}
+
+/// Privacy expressed by a privacy loss distribution (PLD), for tight accounting under composition.
+/// The distance is a PLD, and a measurement is private at a PLD if its own PLD is dominated by it:
+/// its delta is no larger at every epsilon.
+#[cfg(feature="contrib")]
+#[derive(Clone)]
+pub struct PrivacyLossDivergence;
+
+#[cfg(feature="contrib")]
+impl Default for PrivacyLossDivergence {
+    fn default() -> Self { PrivacyLossDivergence }
+}
+#[cfg(feature="contrib")]
+impl PartialEq for PrivacyLossDivergence {
+    fn eq(&self, _other: &Self) -> bool { true }
+}
+#[cfg(feature="contrib")]
+impl Debug for PrivacyLossDivergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "PrivacyLossDivergence()")
+    }
+}
+#[cfg(feature="contrib")]
+impl Measure for PrivacyLossDivergence {
+    type Distance = PrivacyLossDistribution;
+}
+
 /// Metrics
 #[derive(Clone)]
 pub struct SymmetricDistance;
@@ -91,6 +191,48 @@ impl Metric for SubstituteDistance {
 
 impl DatasetMetric for SubstituteDistance {}
 
+/// The distance between partitioned datasets: the largest symmetric distance between any pair of
+/// corresponding partitions, and the number of partitions that differ.
+#[derive(Clone)]
+pub struct PartitionDistance;
+
+impl Default for PartitionDistance {
+    fn default() -> Self { PartitionDistance }
+}
+
+impl PartialEq for PartitionDistance {
+    fn eq(&self, _other: &Self) -> bool { true }
+}
+impl Debug for PartitionDistance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "PartitionDistance()")
+    }
+}
+impl Metric for PartitionDistance {
+    type Distance = (IntDistance, IntDistance);
+}
+
+/// The distance between two individual records: 0 if they are equal, and 1 otherwise.
+/// This is the input metric of local mechanisms, where each record is privatized on its own.
+#[derive(Clone)]
+pub struct DiscreteDistance;
+
+impl Default for DiscreteDistance {
+    fn default() -> Self { DiscreteDistance }
+}
+
+impl PartialEq for DiscreteDistance {
+    fn eq(&self, _other: &Self) -> bool { true }
+}
+impl Debug for DiscreteDistance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "DiscreteDistance()")
+    }
+}
+impl Metric for DiscreteDistance {
+    type Distance = IntDistance;
+}
+
 // Sensitivity in P-space
 pub struct LpDistance<Q, const P: usize>(PhantomData<Q>);
 impl<Q, const P: usize> Default for LpDistance<Q, P> {
@@ -116,6 +258,28 @@ impl<Q, const P: usize> SensitivityMetric for LpDistance<Q, P> {}
 pub type L1Distance<Q> = LpDistance<Q, 1>;
 pub type L2Distance<Q> = LpDistance<Q, 2>;
 
+// Sensitivity in the max norm
+pub struct LInfDistance<Q>(PhantomData<Q>);
+impl<Q> Default for LInfDistance<Q> {
+    fn default() -> Self { LInfDistance(PhantomData) }
+}
+
+impl<Q> Clone for LInfDistance<Q> {
+    fn clone(&self) -> Self { Self::default() }
+}
+impl<Q> PartialEq for LInfDistance<Q> {
+    fn eq(&self, _other: &Self) -> bool { true }
+}
+impl<Q> Debug for LInfDistance<Q> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "LInfDistance()")
+    }
+}
+impl<Q> Metric for LInfDistance<Q> {
+    type Distance = Q;
+}
+impl<Q> SensitivityMetric for LInfDistance<Q> {}
+
 
 pub struct AbsoluteDistance<Q>(PhantomData<Q>);
 impl<Q> Default for AbsoluteDistance<Q> {
@@ -1,10 +1,23 @@
 use std::any::Any;
 use std::rc::Rc;
 
+#[cfg(all(feature="floating-point", feature="contrib"))]
+use num::Float;
+
+#[cfg(feature="contrib")]
+use crate::comb::BasicCompositionMeasure;
 use crate::core::{Domain, Function, Measure, Measurement, Metric, PrivacyRelation};
+#[cfg(all(feature="floating-point", feature="contrib"))]
+use crate::core::Transformation;
+#[cfg(all(feature="floating-point", feature="contrib"))]
+use crate::dist::{AbsoluteDistance, MaxDivergence};
 use crate::dom::AllDomain;
 use crate::error::*;
-use crate::traits::{FallibleSub, MeasureDistance, MetricDistance, CheckNull};
+#[cfg(all(feature="floating-point", feature="contrib"))]
+use crate::samplers::SampleLaplace;
+use crate::traits::{FallibleSub, MeasureDistance, MetricDistance, CheckNull, ProductOrd};
+#[cfg(all(feature="floating-point", feature="contrib"))]
+use crate::traits::ExactIntCast;
 
 /// A structure tracking the state of an interactive measurement queryable.
 /// It's generic over state (S), query (Q), answer (A), so it can be used for any
@@ -14,13 +27,14 @@ pub struct Queryable<S, Q, A> {
     /// temporarily, during transitions.
     state: Option<S>,
     /// The transition function of the Queryable. Takes the current state and a query, returns
-    /// the new state and the answer.
-    transition: Rc<dyn Fn(S, &Q) -> Fallible<(S, A)>>,
+    /// the new state and the answer. The state is returned even when the query fails,
+    /// so that a refused query does not end the interaction.
+    transition: Rc<dyn Fn(S, &Q) -> (S, Fallible<A>)>,
 }
 
 impl<S, Q, A> Queryable<S, Q, A> {
     /// Constructs a Queryable with initial state and transition function.
-    pub fn new(initial: S, transition: impl Fn(S, &Q) -> Fallible<(S, A)> + 'static) -> Self {
+    pub fn new(initial: S, transition: impl Fn(S, &Q) -> (S, Fallible<A>) + 'static) -> Self {
         Queryable {
             state: Some(initial),
             transition: Rc::new(transition),
@@ -32,10 +46,10 @@ impl<S, Q, A> Queryable<S, Q, A> {
         // Take temporary ownership of the state from this struct.
         let state = self.state.take().unwrap_assert("Queryable state is only accessed in this method, always replaced.");
         // Obtain then new state and answer.
-        let new_state_answer = (self.transition)(state, query)?;
+        let (new_state, answer) = (self.transition)(state, query);
         // Restore ownership of the state into this struct.
-        self.state.replace(new_state_answer.0);
-        Ok(new_state_answer.1)
+        self.state.replace(new_state);
+        answer
     }
 }
 
@@ -50,6 +64,39 @@ impl<S, Q, A> CheckNull for Queryable<S, Q, A> { fn is_null(&self) -> bool { fal
 
 pub type InteractiveMeasurement<DI, DO, MI, MO, S, Q> = Measurement<DI, AllDomain<Queryable<S, Q, <DO as Domain>::Carrier>>, MI, MO>;
 
+/// Checks that a measurement (of a query) has the domains, metric and measure of a Queryable.
+fn check_query_types<DI: Domain, DO: Domain, MI: Metric, MO: Measure>(
+    measurement: &Measurement<DI, DO, MI, MO>,
+    input_domain: &DI, output_domain: &DO, input_metric: &MI, output_measure: &MO,
+) -> Fallible<()> {
+    if &measurement.input_domain != input_domain {
+        return fallible!(DomainMismatch, "wrong query input domain")
+    } else if &measurement.output_domain != output_domain {
+        return fallible!(DomainMismatch, "wrong query output domain")
+    } else if &measurement.input_metric != input_metric {
+        return fallible!(MetricMismatch, "wrong query input metric")
+    } else if &measurement.output_measure != output_measure {
+        return fallible!(MeasureMismatch, "wrong query output measure")
+    }
+    Ok(())
+}
+
+/// Checks that the query is private at its d_out, where its d_in may not be less than the `d_in` of the Queryable.
+fn check_query_privacy<DI: Domain, DO: Domain, MI: Metric, MO: Measure>(
+    query: &AcQuery<DI, DO, MI, MO>, d_in: &MI::Distance,
+) -> Fallible<()> where MI::Distance: MetricDistance {
+    let d_in = match &query.d_in {
+        Some(query_d_in) if query_d_in < d_in =>
+            return fallible!(FailedRelation, "query d_in must be at least the d_in of the queryable"),
+        Some(query_d_in) => query_d_in,
+        None => d_in,
+    };
+    if !query.measurement.privacy_relation.eval(d_in, &query.d_out)? {
+        return fallible!(FailedRelation, "query is not private at the given d_out")
+    }
+    Ok(())
+}
+
 /// The state of an adaptive composition Queryable.
 pub struct AcState<DI: Domain, DO: Domain, MI: Metric, MO: Measure> {
     input_domain: DI,
@@ -60,7 +107,7 @@ pub struct AcState<DI: Domain, DO: Domain, MI: Metric, MO: Measure> {
     d_out_budget: MO::Distance,
     data: DI::Carrier,
 }
-impl<DI: Domain, DO: Domain, MI: Metric, MO: Measure> AcState<DI, DO, MI, MO> where MO::Distance: MeasureDistance {
+impl<DI: Domain, DO: Domain, MI: Metric, MO: Measure> AcState<DI, DO, MI, MO> where MO::Distance: MeasureDistance + ProductOrd {
     pub fn new(
         input_domain: DI,
         output_domain: DO,
@@ -83,46 +130,65 @@ impl<DI: Domain, DO: Domain, MI: Metric, MO: Measure> AcState<DI, DO, MI, MO> wh
 
     /// Checks that a measurement (of a query) is compatible with this Queryable state.
     fn check_types(&self, measurement: &Measurement<DI, DO, MI, MO>) -> Fallible<()> {
-        if measurement.input_domain != self.input_domain {
-            return fallible!(DomainMismatch, "wrong query input domain")
-        } else if measurement.output_domain != self.output_domain {
-            return fallible!(DomainMismatch, "wrong query output domain")
-        } else if measurement.input_metric != self.input_metric {
-            return fallible!(MetricMismatch, "wrong query input metric")
-        } else if measurement.output_measure != self.output_measure {
-            return fallible!(MeasureMismatch, "wrong query output measure")
-        }
-        Ok(())
+        check_query_types(measurement, &self.input_domain, &self.output_domain, &self.input_metric, &self.output_measure)
     }
 
-    /// Checks that there is adequate budget in this Queryable state.
-    fn check_budget(&self, privacy_relation: &PrivacyRelation<MI, MO>, d_out_query: &MO::Distance) -> Fallible<()> {
-        privacy_relation.eval(&self.d_in_budget, d_out_query)?;
-        if d_out_query > &self.d_out_budget {
+    /// Checks that the query is private at its d_out, and that there is adequate budget in this Queryable state.
+    fn check_budget(&self, query: &AcQuery<DI, DO, MI, MO>) -> Fallible<()>
+        where MI::Distance: MetricDistance {
+        check_query_privacy(query, &self.d_in_budget)?;
+        if !query.d_out.is_within(&self.d_out_budget) {
             return fallible!(FailedRelation, "not enough budget")
         }
         Ok(())
     }
 
-    /// Updates this Queryable state by consuming the given amount of budget.
-    fn update(self, d_out_query: &MO::Distance) -> Fallible<Self> {
-        Ok(Self { d_out_budget: self.d_out_budget.sub(d_out_query)?, ..self })
+    /// Answers a query, consuming budget from this Queryable state.
+    fn answer(&mut self, query: &AcQuery<DI, DO, MI, MO>) -> Fallible<DO::Carrier>
+        where MI::Distance: MetricDistance,
+              MO::Distance: Clone + MeasureDistance {
+        self.check_types(&query.measurement)?;
+        self.check_budget(query)?;
+        // The budget is consumed before invoking, so that failed invocations are also charged.
+        self.d_out_budget = self.d_out_budget.clone().sub(&query.d_out)?;
+        query.measurement.invoke(&self.data)
     }
 
     /// Processes a query, generating a new Queryable state.
-    fn transition(self, (measurement, d_out_query): &AcQuery<DI, DO, MI, MO>) -> Fallible<(Self, DO::Carrier)>
-        where MO::Distance: Clone + MeasureDistance {
-        self.check_types(measurement)?;
-        self.check_budget(&measurement.privacy_relation, d_out_query)?;
-        let res = measurement.invoke(&self.data)?;
-        let new = self.update(d_out_query)?;
-        Ok((new, res))
+    fn transition(mut self, query: &AcQuery<DI, DO, MI, MO>) -> (Self, Fallible<DO::Carrier>)
+        where MI::Distance: MetricDistance,
+              MO::Distance: Clone + MeasureDistance {
+        let answer = self.answer(query);
+        (self, answer)
     }
 }
-type AcQuery<DI, DO, MI, MO> = (Measurement<DI, DO, MI, MO>, <MO as Measure>::Distance);
-type AcQueryable<DI, DO, MI, MO> = Queryable<AcState<DI, DO, MI, MO>, AcQuery<DI, DO, MI, MO>, <DO as Domain>::Carrier>;
-type AcMeasurement<DI, DO, MI, MO> = InteractiveMeasurement<DI, DO, MI, MO, AcState<DI, DO, MI, MO>, AcQuery<DI, DO, MI, MO>>;
 
+/// A query to an adaptive composition Queryable: a measurement to release, and the privacy loss to charge for it.
+pub struct AcQuery<DI: Domain, DO: Domain, MI: Metric, MO: Measure> {
+    pub measurement: Measurement<DI, DO, MI, MO>,
+    /// The input distance the measurement is checked at. Defaults to the d_in of the Queryable.
+    pub d_in: Option<MI::Distance>,
+    pub d_out: MO::Distance,
+}
+impl<DI: Domain, DO: Domain, MI: Metric, MO: Measure> AcQuery<DI, DO, MI, MO> {
+    pub fn new(measurement: Measurement<DI, DO, MI, MO>, d_out: MO::Distance) -> Self {
+        AcQuery { measurement, d_in: None, d_out }
+    }
+    pub fn new_with_d_in(measurement: Measurement<DI, DO, MI, MO>, d_in: MI::Distance, d_out: MO::Distance) -> Self {
+        AcQuery { measurement, d_in: Some(d_in), d_out }
+    }
+}
+pub type AcQueryable<DI, DO, MI, MO> = Queryable<AcState<DI, DO, MI, MO>, AcQuery<DI, DO, MI, MO>, <DO as Domain>::Carrier>;
+pub type AcMeasurement<DI, DO, MI, MO> = InteractiveMeasurement<DI, DO, MI, MO, AcState<DI, DO, MI, MO>, AcQuery<DI, DO, MI, MO>>;
+
+/// Construct an interactive measurement that answers a sequence of adaptively chosen queries.
+/// Each query is answered only if its measurement is private at its d_out, and the d_outs are
+/// deducted from `d_out_budget`. Budgets are checked component by component, so a query must fit in every component,
+/// like both the epsilon and delta of approximate DP.
+///
+/// The result is private at any d_out of at least `d_out_budget`, so it may itself be the measurement of a query
+/// to another adaptive composition (converted with `into_poly()`). Each child Queryable is charged
+/// to its parent in full when it is spawned, so any number of children may be open at once.
 pub fn make_adaptive_composition<DI, DO, MI, MO>(
     input_domain: DI,
     output_domain: DO,
@@ -137,7 +203,7 @@ pub fn make_adaptive_composition<DI, DO, MI, MO>(
           MI: 'static + Metric,
           MI::Distance: 'static + MetricDistance + Clone,
           MO: 'static + Measure,
-          MO::Distance: 'static + MeasureDistance + Clone {
+          MO::Distance: 'static + MeasureDistance + ProductOrd + Clone {
     AcMeasurement::new(
         input_domain.clone(),
         AllDomain::new(),
@@ -149,14 +215,300 @@ pub fn make_adaptive_composition<DI, DO, MI, MO>(
         })),
         input_metric,
         output_measure,
-        PrivacyRelation::new(move |d_in, d_out| d_in <= &d_in_budget && d_out <= &d_out_budget),
+        PrivacyRelation::new(move |d_in, d_out| d_in <= &d_in_budget && d_out_budget.is_within(d_out)),
     )
 }
 
+/// A query to an odometer or filter Queryable.
+#[cfg(feature="contrib")]
+pub enum OdometerQuery<DI: Domain, DO: Domain, MI: Metric, MO: BasicCompositionMeasure> {
+    /// Release the measurement in the query, and add its d_out to the privacy loss.
+    Invoke(AcQuery<DI, DO, MI, MO>),
+    /// Ask for the privacy loss of all queries answered so far.
+    PrivacyLoss,
+    /// Ask for the budget left under the cap. Only filters have a cap.
+    RemainingBudget,
+}
+
+/// An answer from an odometer or filter Queryable, with one variant per kind of query.
+#[cfg(feature="contrib")]
+#[derive(Debug, PartialEq)]
+pub enum OdometerAnswer<DO: Domain, MO: BasicCompositionMeasure> {
+    Invoke(DO::Carrier),
+    PrivacyLoss(MO::Distance),
+    RemainingBudget(MO::Distance),
+}
+
+#[cfg(feature="contrib")]
+impl<DO: Domain, MO: BasicCompositionMeasure> CheckNull for OdometerAnswer<DO, MO> { fn is_null(&self) -> bool { false } }
+
+/// The state of an odometer or filter Queryable.
+#[cfg(feature="contrib")]
+pub struct OdometerState<DI: Domain, DO: Domain, MI: Metric, MO: BasicCompositionMeasure> {
+    input_domain: DI,
+    output_domain: DO,
+    input_metric: MI,
+    output_measure: MO,
+    d_in: MI::Distance,
+    /// The cap on the privacy loss, if the Queryable is a filter.
+    d_out_cap: Option<MO::Distance>,
+    /// The privacy loss of all queries answered so far.
+    privacy_loss: MO::Distance,
+    data: DI::Carrier,
+}
+
+#[cfg(feature="contrib")]
+impl<DI: Domain, DO: Domain, MI: Metric, MO: BasicCompositionMeasure> OdometerState<DI, DO, MI, MO>
+    where MI::Distance: Clone + MetricDistance,
+          MO::Distance: Clone + MeasureDistance {
+    /// Answers a query to release a measurement, adding its d_out to the privacy loss.
+    fn invoke(&mut self, query: &AcQuery<DI, DO, MI, MO>) -> Fallible<DO::Carrier> {
+        check_query_types(&query.measurement, &self.input_domain, &self.output_domain, &self.input_metric, &self.output_measure)?;
+        check_query_privacy(query, &self.d_in)?;
+        let privacy_loss = self.output_measure.compose(vec![self.privacy_loss.clone(), query.d_out.clone()])?;
+        if let Some(d_out_cap) = &self.d_out_cap {
+            if !self.output_measure.is_within(&privacy_loss, d_out_cap)? {
+                return fallible!(FailedRelation, "not enough budget")
+            }
+        }
+        // The privacy loss is recorded before invoking, so that failed invocations are also counted.
+        self.privacy_loss = privacy_loss;
+        query.measurement.invoke(&self.data)
+    }
+
+    fn answer(&mut self, query: &OdometerQuery<DI, DO, MI, MO>) -> Fallible<OdometerAnswer<DO, MO>> {
+        Ok(match query {
+            OdometerQuery::Invoke(query) => OdometerAnswer::Invoke(self.invoke(query)?),
+            OdometerQuery::PrivacyLoss => OdometerAnswer::PrivacyLoss(self.privacy_loss.clone()),
+            OdometerQuery::RemainingBudget => {
+                let d_out_cap = self.d_out_cap.clone()
+                    .ok_or_else(|| err!(FailedFunction, "an odometer has no cap, so there is no remaining budget"))?;
+                OdometerAnswer::RemainingBudget(d_out_cap.sub(&self.privacy_loss)?)
+            }
+        })
+    }
+
+    /// Processes a query, generating a new Queryable state.
+    fn transition(mut self, query: &OdometerQuery<DI, DO, MI, MO>) -> (Self, Fallible<OdometerAnswer<DO, MO>>) {
+        let answer = self.answer(query);
+        (self, answer)
+    }
+}
+
+#[cfg(feature="contrib")]
+type OdometerQueryable<DI, DO, MI, MO> = Queryable<OdometerState<DI, DO, MI, MO>, OdometerQuery<DI, DO, MI, MO>, OdometerAnswer<DO, MO>>;
+
+#[cfg(feature="contrib")]
+fn make_odometer_function<DI, DO, MI, MO>(
+    input_domain: DI, output_domain: DO, input_metric: MI, output_measure: MO,
+    d_in: MI::Distance, d_out_cap: Option<MO::Distance>,
+) -> Fallible<Function<DI, AllDomain<OdometerQueryable<DI, DO, MI, MO>>>>
+    where DI: 'static + Domain,
+          DI::Carrier: Clone,
+          DO: 'static + Domain,
+          MI: 'static + Metric,
+          MI::Distance: 'static + MetricDistance + Clone,
+          MO: 'static + BasicCompositionMeasure,
+          MO::Distance: 'static + MeasureDistance + Clone {
+    // the privacy loss of no queries
+    let privacy_loss = output_measure.compose(vec![])?;
+    Ok(Function::new(move |arg: &DI::Carrier| -> OdometerQueryable<DI, DO, MI, MO> {
+        OdometerQueryable::new(
+            OdometerState {
+                input_domain: input_domain.clone(),
+                output_domain: output_domain.clone(),
+                input_metric: input_metric.clone(),
+                output_measure: output_measure.clone(),
+                d_in: d_in.clone(),
+                d_out_cap: d_out_cap.clone(),
+                privacy_loss: privacy_loss.clone(),
+                data: arg.clone(),
+            },
+            |s, q| s.transition(q))
+    }))
+}
+
+/// Construct a privacy odometer: an interactive measurement that answers adaptively chosen queries
+/// without a preset budget, and keeps a running total of their privacy loss.
+///
+/// The privacy loss is only known once the interaction is over, so the privacy relation of the odometer
+/// always fails. Ask the queryable for its `PrivacyLoss`, or use `make_privacy_filter` to bound it up front.
+#[cfg(feature="contrib")]
+pub fn make_privacy_odometer<DI, DO, MI, MO>(
+    input_domain: DI,
+    output_domain: DO,
+    input_metric: MI,
+    output_measure: MO,
+    d_in: MI::Distance,
+) -> Fallible<InteractiveMeasurement<DI, AllDomain<OdometerAnswer<DO, MO>>, MI, MO, OdometerState<DI, DO, MI, MO>, OdometerQuery<DI, DO, MI, MO>>>
+    where DI: 'static + Domain,
+          DI::Carrier: Clone,
+          DO: 'static + Domain,
+          MI: 'static + Metric,
+          MI::Distance: 'static + MetricDistance + Clone,
+          MO: 'static + BasicCompositionMeasure,
+          MO::Distance: 'static + MeasureDistance + Clone {
+    Ok(Measurement::new(
+        input_domain.clone(),
+        AllDomain::new(),
+        make_odometer_function(input_domain, output_domain, input_metric.clone(), output_measure.clone(), d_in, None)?,
+        input_metric,
+        output_measure,
+        PrivacyRelation::new_fallible(|_d_in, _d_out|
+            fallible!(FailedRelation, "the privacy loss of an odometer is not known until the interaction is over")),
+    ))
+}
+
+/// Construct a privacy filter: a privacy odometer that refuses any query that would bring
+/// the total privacy loss above `d_out_cap`.
+#[cfg(feature="contrib")]
+pub fn make_privacy_filter<DI, DO, MI, MO>(
+    input_domain: DI,
+    output_domain: DO,
+    input_metric: MI,
+    output_measure: MO,
+    d_in: MI::Distance,
+    d_out_cap: MO::Distance,
+) -> Fallible<InteractiveMeasurement<DI, AllDomain<OdometerAnswer<DO, MO>>, MI, MO, OdometerState<DI, DO, MI, MO>, OdometerQuery<DI, DO, MI, MO>>>
+    where DI: 'static + Domain,
+          DI::Carrier: Clone,
+          DO: 'static + Domain,
+          MI: 'static + Metric,
+          MI::Distance: 'static + MetricDistance + Clone,
+          MO: 'static + BasicCompositionMeasure,
+          MO::Distance: 'static + MeasureDistance + Clone {
+    Ok(Measurement::new(
+        input_domain.clone(),
+        AllDomain::new(),
+        make_odometer_function(input_domain, output_domain, input_metric.clone(), output_measure.clone(), d_in.clone(), Some(d_out_cap.clone()))?,
+        input_metric,
+        output_measure.clone(),
+        PrivacyRelation::new_fallible(move |d_in_query, d_out|
+            Ok(d_in_query <= &d_in && output_measure.is_within(&d_out_cap, d_out)?)),
+    ))
+}
+
+
+/// A query to a sparse vector Queryable: a scalar-valued transformation with sensitivity 1.
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub type SvtQuery<DI, MI, T> = Transformation<DI, AllDomain<T>, MI, AbsoluteDistance<T>>;
+
+/// The state of a sparse vector Queryable.
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub struct SvtState<DI: Domain, MI: Metric, T> {
+    input_domain: DI,
+    input_metric: MI,
+    d_in: MI::Distance,
+    threshold: T,
+    scale: T,
+    /// The threshold perturbed with laplace(`scale`) noise. It is resampled after every positive answer.
+    noisy_threshold: T,
+    /// The number of positive answers left before the Queryable halts.
+    positives_remaining: usize,
+    data: DI::Carrier,
+}
+
+#[cfg(all(feature="floating-point", feature="contrib"))]
+impl<DI: Domain, MI: Metric, T> SvtState<DI, MI, T>
+    where MI::Distance: Clone + MetricDistance,
+          T: Float + SampleLaplace + CheckNull {
+
+    /// Checks that a query is compatible with this Queryable state, and has sensitivity at most 1.
+    fn check_query(&self, query: &SvtQuery<DI, MI, T>) -> Fallible<()> {
+        if query.input_domain != self.input_domain {
+            return fallible!(DomainMismatch, "wrong query input domain")
+        } else if query.input_metric != self.input_metric {
+            return fallible!(MetricMismatch, "wrong query input metric")
+        }
+        if !query.stability_relation.eval(&self.d_in, &T::one())? {
+            return fallible!(FailedRelation, "query must have sensitivity at most 1")
+        }
+        Ok(())
+    }
+
+    /// Answers whether the noisy query is above the noisy threshold.
+    fn answer(&mut self, query: &SvtQuery<DI, MI, T>) -> Fallible<bool> {
+        if self.positives_remaining == 0 {
+            return fallible!(FailedFunction, "the sparse vector queryable has halted")
+        }
+        self.check_query(query)?;
+        let _2 = T::one() + T::one();
+        let value = query.function.eval(&self.data)?;
+        let above = T::sample_laplace(value, _2 * self.scale, false)? >= self.noisy_threshold;
+        if above {
+            self.positives_remaining -= 1;
+            self.noisy_threshold = T::sample_laplace(self.threshold, self.scale, false)?;
+        }
+        Ok(above)
+    }
+
+    /// Processes a query, generating a new Queryable state.
+    fn transition(mut self, query: &SvtQuery<DI, MI, T>) -> (Self, Fallible<bool>) {
+        let answer = self.answer(query);
+        (self, answer)
+    }
+}
+
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub type SvtQueryable<DI, MI, T> = Queryable<SvtState<DI, MI, T>, SvtQuery<DI, MI, T>, bool>;
+
+/// Construct an interactive measurement that implements the sparse vector technique (AboveThreshold).
+///
+/// The queryable answers a stream of adaptively chosen queries, each a transformation with sensitivity 1 at `d_in`,
+/// with whether the query value is above `threshold`. The threshold is perturbed with laplace(`scale`) noise,
+/// and each query value with laplace(2 * `scale`) noise. After `max_positives` positive answers the queryable halts.
+///
+/// Any number of negative answers may be released. The relation holds whenever epsilon >= 2 * `max_positives` / `scale`.
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub fn make_sparse_vector<DI, MI, T>(
+    input_domain: DI,
+    input_metric: MI,
+    d_in: MI::Distance,
+    threshold: T,
+    scale: T,
+    max_positives: usize,
+) -> Fallible<InteractiveMeasurement<DI, AllDomain<bool>, MI, MaxDivergence<T>, SvtState<DI, MI, T>, SvtQuery<DI, MI, T>>>
+    where DI: 'static + Domain,
+          DI::Carrier: Clone,
+          MI: 'static + Metric,
+          MI::Distance: 'static + MetricDistance + Clone,
+          T: 'static + Float + SampleLaplace + ExactIntCast<usize> + CheckNull {
+    if scale.is_sign_negative() || scale.is_zero() {
+        return fallible!(MakeMeasurement, "scale must be positive")
+    }
+    if max_positives == 0 {
+        return fallible!(MakeMeasurement, "max_positives must be positive")
+    }
+    let _2 = T::one() + T::one();
+    let epsilon = _2 * T::exact_int_cast(max_positives)? / scale;
+
+    Ok(Measurement::new(
+        input_domain.clone(),
+        AllDomain::new(),
+        Function::new_fallible(enclose!((input_domain, input_metric, d_in), move |arg: &DI::Carrier| -> Fallible<SvtQueryable<DI, MI, T>> {
+            Ok(SvtQueryable::new(
+                SvtState {
+                    input_domain: input_domain.clone(),
+                    input_metric: input_metric.clone(),
+                    d_in: d_in.clone(),
+                    threshold,
+                    scale,
+                    noisy_threshold: T::sample_laplace(threshold, scale, false)?,
+                    positives_remaining: max_positives,
+                    data: arg.clone(),
+                },
+                |s, q| s.transition(q)))
+        })),
+        input_metric,
+        MaxDivergence::default(),
+        PrivacyRelation::new(move |d_in_query, eps| d_in_query <= &d_in && eps >= &epsilon),
+    ))
+}
+
 
 #[cfg(test)]
 mod tests {
-    use crate::dist::{MaxDivergence, AbsoluteDistance, SymmetricDistance};
+    use crate::dist::{MaxDivergence, AbsoluteDistance, SmoothedMaxDivergence, SymmetricDistance};
     use crate::dom::VectorDomain;
     use crate::error::*;
     use crate::meas::*;
@@ -172,7 +524,7 @@ mod tests {
             Function::new(|a: &i32| TO::from(a.clone())),
             AbsoluteDistance::<f64>::default(),
             MaxDivergence::<f64>::default(),
-            PrivacyRelation::new(|d_in, d_out| d_out <= d_in),
+            PrivacyRelation::new(|d_in, d_out| d_out >= d_in),
         )
     }
 
@@ -182,13 +534,13 @@ mod tests {
         let meas2 = make_dummy_meas::<i32>();
 
         let data = 999;
-        let d_in_budget = 1.0;
+        let d_in_budget = 0.5;
         let d_out_budget = 1.0;
         let adaptive = make_adaptive_composition(meas1.input_domain.clone(), meas1.output_domain.clone(), meas1.input_metric.clone(), meas1.output_measure.clone(), d_in_budget, d_out_budget);
         let mut queryable = adaptive.invoke(&data)?;
-        let res1 = queryable.eval(&(meas1, d_out_budget / 2.0))?;
+        let res1 = queryable.eval(&AcQuery::new(meas1, d_out_budget / 2.0))?;
         assert_eq!(res1, 999);
-        let res2 = queryable.eval(&(meas2, d_out_budget / 2.0))?;
+        let res2 = queryable.eval(&AcQuery::new(meas2, d_out_budget / 2.0))?;
         assert_eq!(res2, 999);
 
         Ok(())
@@ -200,13 +552,13 @@ mod tests {
         let meas2 = make_dummy_meas::<i64>().into_poly();
 
         let data = 999;
-        let d_in_budget = 1.0;
+        let d_in_budget = 0.5;
         let d_out_budget = 1.0;
         let adaptive = make_adaptive_composition(meas1.input_domain.clone(), PolyDomain::new(), meas1.input_metric.clone(), meas1.output_measure.clone(), d_in_budget, d_out_budget);
         let mut queryable = adaptive.invoke(&data)?;
-        let res1: i32 = queryable.eval_poly(&(meas1, d_out_budget / 2.0))?;
+        let res1: i32 = queryable.eval_poly(&AcQuery::new(meas1, d_out_budget / 2.0))?;
         assert_eq!(res1, 999_i32);
-        let res2: i64 = queryable.eval_poly(&(meas2, d_out_budget / 2.0))?;
+        let res2: i64 = queryable.eval_poly(&AcQuery::new(meas2, d_out_budget / 2.0))?;
         assert_eq!(res2, 999_i64);
 
         Ok(())
@@ -218,19 +570,136 @@ mod tests {
         let meas2 = make_dummy_meas::<i32>();
 
         let data = 999;
-        let d_in_budget = 1.0;
+        let d_in_budget = 0.5;
         let d_out_budget = 1.0;
         let adaptive = make_adaptive_composition(meas1.input_domain.clone(), meas1.output_domain.clone(), meas1.input_metric.clone(), meas1.output_measure.clone(), d_in_budget, d_out_budget);
         let mut queryable = adaptive.invoke(&data)?;
-        let res1 = queryable.eval(&(meas1, d_out_budget / 2.0))?;
+        let res1 = queryable.eval(&AcQuery::new(meas1, d_out_budget / 2.0))?;
         assert_eq!(res1, 999);
-        let res2 = queryable.eval(&(meas2, d_out_budget));
+        let res2 = queryable.eval(&AcQuery::new(meas2, d_out_budget));
         // TODO: Would be handy to have a way of comparing Errors for this assertion.
         assert!(res2.is_err());
 
         Ok(())
     }
 
+    #[test]
+    fn test_adaptive_composition_refuses_non_private_query() -> Fallible<()> {
+        let meas1 = make_dummy_meas::<i32>();
+        let meas2 = make_dummy_meas::<i32>();
+
+        let data = 999;
+        let d_in_budget = 1.0;
+        let d_out_budget = 1.0;
+        let adaptive = make_adaptive_composition(meas1.input_domain.clone(), meas1.output_domain.clone(), meas1.input_metric.clone(), meas1.output_measure.clone(), d_in_budget, d_out_budget);
+        let mut queryable = adaptive.invoke(&data)?;
+        // the dummy measurement is only private at d_out >= d_in, so it is under-noised at 0.5
+        let res1 = queryable.eval(&AcQuery::new(meas1, 0.5));
+        assert!(res1.is_err());
+        // the refused query does not consume budget
+        let res2 = queryable.eval(&AcQuery::new(meas2, 1.0))?;
+        assert_eq!(res2, 999);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_adaptive_composition_approximate() -> Fallible<()> {
+        let make_dummy_meas = || Measurement::new(
+            AllDomain::<i32>::new(),
+            AllDomain::<i32>::new(),
+            Function::new(|a: &i32| *a),
+            AbsoluteDistance::<f64>::default(),
+            SmoothedMaxDivergence::<f64>::default(),
+            PrivacyRelation::new(|d_in: &f64, d_out: &(f64, f64)| d_out.0 >= *d_in));
+
+        let adaptive = make_adaptive_composition(
+            AllDomain::new(), AllDomain::new(), AbsoluteDistance::default(), SmoothedMaxDivergence::default(), 1., (1., 1e-6));
+        let mut queryable = adaptive.invoke(&999)?;
+        // epsilon is within budget, but delta overspends it
+        assert!(queryable.eval(&AcQuery::new(make_dummy_meas(), (1., 0.5))).is_err());
+        assert_eq!(queryable.eval(&AcQuery::new(make_dummy_meas(), (1., 1e-6)))?, 999);
+
+        assert!(adaptive.check(&1., &(1., 1e-6))?);
+        assert!(!adaptive.check(&1., &(2., 0.))?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_adaptive_composition_query_d_in() -> Fallible<()> {
+        let meas1 = make_dummy_meas::<i32>();
+        let meas2 = make_dummy_meas::<i32>();
+
+        let data = 999;
+        let d_in_budget = 0.5;
+        let d_out_budget = 1.0;
+        let adaptive = make_adaptive_composition(meas1.input_domain.clone(), meas1.output_domain.clone(), meas1.input_metric.clone(), meas1.output_measure.clone(), d_in_budget, d_out_budget);
+        let mut queryable = adaptive.invoke(&data)?;
+        // a query may not claim a smaller d_in than the queryable
+        let res1 = queryable.eval(&AcQuery::new_with_d_in(meas1, 0.25, 0.25));
+        assert!(res1.is_err());
+        // the relation is checked at the query d_in
+        let res2 = queryable.eval(&AcQuery::new_with_d_in(meas2, 0.75, 0.5));
+        assert!(res2.is_err());
+        let res3 = queryable.eval(&AcQuery::new_with_d_in(make_dummy_meas::<i32>(), 0.75, 0.75))?;
+        assert_eq!(res3, 999);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_adaptive_composition_nested() -> Fallible<()> {
+        type ChildQueryable = AcQueryable<AllDomain<i32>, PolyDomain, AbsoluteDistance<f64>, MaxDivergence<f64>>;
+        let make_child = |d_in_budget: f64, d_out_budget: f64| make_adaptive_composition(
+            AllDomain::<i32>::new(), PolyDomain::new(), AbsoluteDistance::<f64>::default(), MaxDivergence::<f64>::default(), d_in_budget, d_out_budget).into_poly();
+
+        let data = 999;
+        let parent = make_adaptive_composition(AllDomain::<i32>::new(), PolyDomain::new(), AbsoluteDistance::<f64>::default(), MaxDivergence::<f64>::default(), 0.25, 1.0);
+        let mut parent_queryable = parent.invoke(&data)?;
+
+        // a child must be charged at least its own budget, and must cover the parent d_in
+        assert!(parent_queryable.eval(&AcQuery::new(make_child(0.25, 0.6), 0.5)).is_err());
+        assert!(parent_queryable.eval(&AcQuery::new(make_child(0.1, 0.6), 0.6)).is_err());
+
+        // two children stay open at once, with sub-budgets of the parent budget
+        let mut child1: ChildQueryable = parent_queryable.eval_poly(&AcQuery::new(make_child(0.25, 0.75), 0.75))?;
+        let mut child2: ChildQueryable = parent_queryable.eval_poly(&AcQuery::new(make_child(0.25, 0.25), 0.25))?;
+        // the parent budget is spent
+        assert!(parent_queryable.eval(&AcQuery::new(make_dummy_meas::<i32>().into_poly(), 0.5)).is_err());
+
+        let res1: i32 = child1.eval_poly(&AcQuery::new(make_dummy_meas::<i32>().into_poly(), 0.5))?;
+        let res2: i32 = child2.eval_poly(&AcQuery::new(make_dummy_meas::<i32>().into_poly(), 0.25))?;
+        assert_eq!((res1, res2), (999, 999));
+
+        // each child is limited by its own budget
+        assert!(child2.eval(&AcQuery::new(make_dummy_meas::<i32>().into_poly(), 0.5)).is_err());
+        // children can spawn children of their own, out of what remains of their budget
+        assert!(child1.eval(&AcQuery::new(make_child(0.25, 0.3), 0.3)).is_err());
+        let _grandchild: ChildQueryable = child1.eval_poly(&AcQuery::new(make_child(0.25, 0.25), 0.25))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_adaptive_composition_nested_approximate() -> Fallible<()> {
+        type ChildQueryable = AcQueryable<AllDomain<i32>, PolyDomain, AbsoluteDistance<f64>, SmoothedMaxDivergence<f64>>;
+        let make_child = |d_out_budget: (f64, f64)| make_adaptive_composition(
+            AllDomain::<i32>::new(), PolyDomain::new(), AbsoluteDistance::<f64>::default(), SmoothedMaxDivergence::<f64>::default(), 1., d_out_budget).into_poly();
+
+        let parent = make_adaptive_composition(AllDomain::<i32>::new(), PolyDomain::new(), AbsoluteDistance::<f64>::default(), SmoothedMaxDivergence::<f64>::default(), 1., (2., 1e-6));
+        let mut parent_queryable = parent.invoke(&999)?;
+
+        // the child could spend more delta than the parent would deduct
+        assert!(parent_queryable.eval(&AcQuery::new(make_child((1., 0.5)), (1.5, 0.))).is_err());
+        // the child must also fit within the delta of the parent budget
+        assert!(parent_queryable.eval(&AcQuery::new(make_child((1., 0.5)), (1., 0.5))).is_err());
+        let _child: ChildQueryable = parent_queryable.eval_poly(&AcQuery::new(make_child((1., 1e-6)), (1., 1e-6)))?;
+        // the delta of the parent budget is spent
+        assert!(parent_queryable.eval(&AcQuery::new(make_child((1., 1e-7)), (1., 1e-7))).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_adaptive_composition_chain() -> Fallible<()> {
         // Definitions
@@ -257,7 +726,7 @@ mod tests {
             make_count()? >>
             make_base_geometric(1.0 / d_out_query, Some(count_bounds))?
         )?.into_poly();
-        let query1 = (measurement1, d_out_query);
+        let query1 = AcQuery::new(measurement1, d_out_query);
         let _result1: i32 = queryable.eval_poly(&query1)?;
         // println!("_result = {}", result1);
 
@@ -265,12 +734,127 @@ mod tests {
         let measurement2 = (
             make_clamp(val_bounds)? >>
             make_bounded_sum(val_bounds)? >>
-            make_base_laplace(1.0 / d_out_query)?
+            // the sum has sensitivity 10, so the scale must be 10 / d_out_query for d_out_query-DP
+            make_base_laplace(10.0 / d_out_query)?
         )?.into_poly();
-        let query2 = (measurement2, d_out_query);
+        let query2 = AcQuery::new(measurement2, d_out_query);
         let _result2: f64 = queryable.eval_poly(&query2)?;
         // println!("_result = {}", result2);
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature="contrib")]
+    fn test_privacy_odometer() -> Fallible<()> {
+        let odometer = make_privacy_odometer(AllDomain::new(), AllDomain::new(), AbsoluteDistance::default(), MaxDivergence::default(), 1.)?;
+        let mut queryable = odometer.invoke(&999)?;
+        assert_eq!(queryable.eval(&OdometerQuery::PrivacyLoss)?, OdometerAnswer::PrivacyLoss(0.));
+
+        for _ in 0..3 {
+            assert_eq!(queryable.eval(&OdometerQuery::Invoke(AcQuery::new(make_dummy_meas::<i32>(), 1.5)))?, OdometerAnswer::Invoke(999));
+        }
+        assert_eq!(queryable.eval(&OdometerQuery::PrivacyLoss)?, OdometerAnswer::PrivacyLoss(4.5));
+
+        // refused queries are not counted
+        assert!(queryable.eval(&OdometerQuery::Invoke(AcQuery::new(make_dummy_meas::<i32>(), 0.5))).is_err());
+        assert_eq!(queryable.eval(&OdometerQuery::PrivacyLoss)?, OdometerAnswer::PrivacyLoss(4.5));
+
+        assert!(queryable.eval(&OdometerQuery::RemainingBudget).is_err());
+        assert!(odometer.check(&1., &100.).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature="contrib")]
+    fn test_privacy_filter() -> Fallible<()> {
+        let filter = make_privacy_filter(AllDomain::new(), AllDomain::new(), AbsoluteDistance::default(), MaxDivergence::default(), 1., 2.)?;
+        let mut queryable = filter.invoke(&999)?;
+        assert_eq!(queryable.eval(&OdometerQuery::Invoke(AcQuery::new(make_dummy_meas::<i32>(), 1.5)))?, OdometerAnswer::Invoke(999));
+        assert_eq!(queryable.eval(&OdometerQuery::RemainingBudget)?, OdometerAnswer::RemainingBudget(0.5));
+
+        // the cap would be exceeded
+        assert!(queryable.eval(&OdometerQuery::Invoke(AcQuery::new(make_dummy_meas::<i32>(), 1.))).is_err());
+        assert_eq!(queryable.eval(&OdometerQuery::PrivacyLoss)?, OdometerAnswer::PrivacyLoss(1.5));
+
+        assert!(filter.check(&1., &2.)?);
+        assert!(!filter.check(&1., &1.9)?);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature="contrib")]
+    fn test_privacy_filter_approximate() -> Fallible<()> {
+        let make_dummy_meas = || Measurement::new(
+            AllDomain::<i32>::new(),
+            AllDomain::<i32>::new(),
+            Function::new(|a: &i32| *a),
+            AbsoluteDistance::<f64>::default(),
+            SmoothedMaxDivergence::<f64>::default(),
+            PrivacyRelation::new(|d_in: &f64, d_out: &(f64, f64)| d_out.0 >= *d_in));
+        let filter = make_privacy_filter(AllDomain::new(), AllDomain::new(), AbsoluteDistance::default(), SmoothedMaxDivergence::default(), 1., (2., 1e-6))?;
+        let mut queryable = filter.invoke(&999)?;
+        assert_eq!(queryable.eval(&OdometerQuery::Invoke(AcQuery::new(make_dummy_meas(), (1., 1e-6))))?, OdometerAnswer::Invoke(999));
+
+        // epsilon stays under its cap, but delta would go over it
+        assert!(queryable.eval(&OdometerQuery::Invoke(AcQuery::new(make_dummy_meas(), (1., 1e-7)))).is_err());
+        assert_eq!(queryable.eval(&OdometerQuery::PrivacyLoss)?, OdometerAnswer::PrivacyLoss((1., 1e-6)));
+
+        assert!(filter.check(&1., &(2., 1e-6))?);
+        assert!(!filter.check(&1., &(3., 1e-7))?);
+        Ok(())
+    }
+
+    #[test]
+        fn test_sparse_vector() -> Fallible<()> {
+        let svt = make_sparse_vector(
+            VectorDomain::new_all(), SymmetricDistance, 1,
+            5., 0.01, 2)?;
+        assert!(svt.check(&1, &400.)?);
+        assert!(!svt.check(&1, &399.)?);
+        assert!(!svt.check(&2, &400.)?);
+
+        let mut queryable = svt.invoke(&vec![1, 2, 2, 3, 3, 3, 4])?;
+        // 7 records, but only 4 distinct
+        assert!(!queryable.eval(&make_count_distinct::<i32, f64>()?)?);
+        assert!(queryable.eval(&make_count::<i32, f64>()?)?);
+        assert!(!queryable.eval(&make_count_distinct::<i32, f64>()?)?);
+        assert!(queryable.eval(&make_count::<i32, f64>()?)?);
+        // the queryable halts after two positive answers
+        assert!(queryable.eval(&make_count::<i32, f64>()?).is_err());
+        Ok(())
+    }
+
+    #[test]
+        fn test_sparse_vector_rejects_sensitive_query() -> Fallible<()> {
+        let svt = make_sparse_vector(
+            VectorDomain::new_all(), SymmetricDistance, 2,
+            5., 1., 1)?;
+        let mut queryable = svt.invoke(&vec![1, 2, 3])?;
+        // the count has sensitivity 2 at d_in = 2
+        assert!(queryable.eval(&make_count::<i32, f64>()?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature="floating-point", feature="contrib"))]
+    fn test_sparse_vector_chained() -> Fallible<()> {
+        // the sparse vector is private at d_in = 2 for queries with sensitivity 1 at d_in = 2
+        let svt = make_sparse_vector(
+            VectorDomain::new_all(), SymmetricDistance, 2,
+            5., 1., 2)?;
+        let chain = (make_split_lines()? >> make_cast_default::<String, f64>()? >> svt)?;
+        // epsilon = 2 * max_positives / scale, at any d_in up to 2
+        assert!(chain.check(&2, &4.)?);
+        assert!(!chain.check(&2, &3.99)?);
+        assert!(chain.check(&1, &4.)?);
+        assert!(!chain.check(&3, &4.)?);
+
+        let mut queryable = chain.invoke(&"1\n2\n3".to_string())?;
+        // the count has sensitivity 2 at d_in = 2
+        assert!(queryable.eval(&make_count::<f64, f64>()?).is_err());
+        // a sum of values clamped to [0, 0.5] has sensitivity 1 at d_in = 2
+        let _above: bool = queryable.eval(&(make_clamp((0., 0.5))? >> make_bounded_sum((0., 0.5))?)?)?;
+        Ok(())
+    }
 }
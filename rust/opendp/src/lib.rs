@@ -163,7 +163,10 @@ pub mod interactive;
 pub mod meas;
 pub mod poly;
 pub mod samplers;
+pub mod search;
 pub mod traits;
 pub mod trans;
 pub mod comb;
-pub mod accuracy;
\ No newline at end of file
+pub mod accuracy;
+#[cfg(feature="contrib")]
+pub mod pld;
\ No newline at end of file
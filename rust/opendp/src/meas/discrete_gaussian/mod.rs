@@ -0,0 +1,130 @@
+use num::Float;
+
+use crate::core::{Function, Measurement, PrivacyRelation, Domain, SensitivityMetric};
+use crate::dist::{ZeroConcentratedDivergence, L2Distance, AbsoluteDistance};
+use crate::dom::{AllDomain, VectorDomain};
+use crate::error::*;
+use crate::samplers::SampleDiscreteGaussian;
+use crate::traits::{InfCast, CheckNull};
+
+
+pub trait DiscreteGaussianDomain: Domain {
+    type InputMetric: SensitivityMetric<Distance=Self::Atom> + Default;
+    type Atom;
+    fn new() -> Self;
+    fn noise_function(scale: f64) -> Function<Self, Self>;
+}
+
+
+impl<T> DiscreteGaussianDomain for AllDomain<T>
+    where T: 'static + Clone + SampleDiscreteGaussian + CheckNull {
+    type InputMetric = AbsoluteDistance<T>;
+    type Atom = T;
+
+    fn new() -> Self { AllDomain::new() }
+    fn noise_function(scale: f64) -> Function<Self, Self> {
+        Function::new_fallible(move |arg: &Self::Carrier|
+            T::sample_discrete_gaussian(arg.clone(), scale))
+    }
+}
+
+impl<T> DiscreteGaussianDomain for VectorDomain<AllDomain<T>>
+    where T: 'static + Clone + SampleDiscreteGaussian + CheckNull {
+    type InputMetric = L2Distance<T>;
+    type Atom = T;
+
+    fn new() -> Self { VectorDomain::new_all() }
+    fn noise_function(scale: f64) -> Function<Self, Self> {
+        Function::new_fallible(move |arg: &Self::Carrier| arg.iter()
+            .map(|v| T::sample_discrete_gaussian(v.clone(), scale))
+            .collect())
+    }
+}
+
+/// Make a Measurement that adds noise from the discrete gaussian(`scale`) distribution to integer inputs,
+/// with a privacy relation expressed under zero-concentrated DP.
+/// The relation holds whenever rho >= (d_in / scale)^2 / 2.
+///
+/// Noise is sampled exactly, following [Canonne, Kamath and Steinke](https://arxiv.org/abs/2004.00010).
+pub fn make_base_discrete_gaussian<D, QO>(
+    scale: QO
+) -> Fallible<Measurement<D, D, D::InputMetric, ZeroConcentratedDivergence<QO>>>
+    where D: 'static + DiscreteGaussianDomain,
+          D::Atom: 'static + Clone,
+          QO: 'static + Float + InfCast<D::Atom>,
+          f64: From<QO> {
+    if scale.is_sign_negative() { return fallible!(MakeMeasurement, "scale must not be negative") }
+
+    Ok(Measurement::new(
+        D::new(),
+        D::new(),
+        D::noise_function(f64::from(scale)),
+        D::InputMetric::default(),
+        ZeroConcentratedDivergence::default(),
+        PrivacyRelation::new_all(
+            // relation
+            move |d_in: &D::Atom, &rho: &QO| {
+                let d_in = QO::inf_cast(d_in.clone())?;
+                if d_in.is_sign_negative() {
+                    return fallible!(InvalidDistance, "discrete gaussian mechanism: input sensitivity must be non-negative")
+                }
+                if rho.is_sign_negative() {
+                    return fallible!(InvalidDistance, "discrete gaussian mechanism: rho must be non-negative")
+                }
+                if d_in.is_zero() { return Ok(true) }
+                let _2 = QO::one() + QO::one();
+                Ok(rho >= (d_in / scale).powi(2) / _2)
+            },
+            // forward map
+            Some(move |d_in: &D::Atom| {
+                let d_in = QO::inf_cast(d_in.clone())?;
+                let _2 = QO::one() + QO::one();
+                Ok(Box::new((d_in / scale).powi(2) / _2))
+            }),
+            None::<fn(&_) -> _>)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_discrete_gaussian_mechanism() -> Fallible<()> {
+        let measurement = make_base_discrete_gaussian::<AllDomain<i32>, f64>(2.0)?;
+        let _ret = measurement.invoke(&205)?;
+
+        // rho = (1 / 2)^2 / 2
+        assert!(measurement.check(&1, &0.125)?);
+        assert!(!measurement.check(&1, &0.124)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_vector_discrete_gaussian_mechanism() -> Fallible<()> {
+        let measurement = make_base_discrete_gaussian::<VectorDomain<AllDomain<i64>>, f64>(0.5)?;
+        let _ret = measurement.invoke(&vec![1, 2, 3, 4])?;
+
+        assert!(measurement.check(&1, &2.0)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_discrete_gaussian_zero_scale() -> Fallible<()> {
+        let measurement = make_base_discrete_gaussian::<AllDomain<u8>, f64>(0.0)?;
+        assert_eq!(measurement.invoke(&7)?, 7);
+        Ok(())
+    }
+
+    #[test]
+    fn test_discrete_gaussian_moments() -> Fallible<()> {
+        let measurement = make_base_discrete_gaussian::<VectorDomain<AllDomain<i64>>, f64>(3.0)?;
+        let samples = measurement.invoke(&vec![0; 2000])?;
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<i64>() as f64 / n;
+        let variance = samples.iter().map(|&v| (v as f64 - mean).powi(2)).sum::<f64>() / n;
+        // the variance of the discrete gaussian is slightly less than scale^2 = 9
+        assert!(mean.abs() < 0.5, "mean: {}", mean);
+        assert!((6.5..11.).contains(&variance), "variance: {}", variance);
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use num::{BigRational, Float};
+
+use crate::core::{Function, Measurement, PrivacyRelation};
+use crate::dist::{LInfDistance, MaxDivergence};
+use crate::dom::{AllDomain, VectorDomain};
+use crate::error::*;
+use crate::samplers::{sample_bernoulli_exp, SampleUniform, SampleUniformInt};
+use crate::traits::{CheckNull, ExactIntCast, TotalOrd};
+
+/// The noise distribution used to privately select a candidate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SelectionNoise {
+    /// Gumbel noise. The noisy argmax is a sample from the exponential mechanism.
+    Gumbel,
+    /// Exponential noise. The noisy argmax is report-noisy-max.
+    Exponential,
+}
+
+impl FromStr for SelectionNoise {
+    type Err = Error;
+    fn from_str(s: &str) -> Fallible<Self> {
+        match s.to_lowercase().as_str() {
+            "gumbel" => Ok(SelectionNoise::Gumbel),
+            "exponential" => Ok(SelectionNoise::Exponential),
+            _ => fallible!(FailedCast, "unrecognized selection noise {:?}. Expected \"gumbel\" or \"exponential\"", s)
+        }
+    }
+}
+
+/// Sample uniformly from the open interval (0, 1), so that the logarithm is finite.
+fn sample_open_uniform<T: Float + SampleUniform>() -> Fallible<T> {
+    loop {
+        let sample = T::sample_standard_uniform(false)?;
+        if sample > T::zero() { return Ok(sample) }
+    }
+}
+
+impl SelectionNoise {
+    pub(crate) fn sample<T: Float + SampleUniform>(&self) -> Fallible<T> {
+        let uniform = sample_open_uniform::<T>()?;
+        Ok(match self {
+            SelectionNoise::Gumbel => -(-uniform.ln()).ln(),
+            SelectionNoise::Exponential => -uniform.ln()
+        })
+    }
+}
+
+/// The privacy relation shared by selection mechanisms over score vectors: epsilon >= 2 * d_in / scale.
+fn make_selection_privacy_relation<TIA: 'static + Float>(scale: TIA) -> PrivacyRelation<LInfDistance<TIA>, MaxDivergence<TIA>> {
+    let _2 = TIA::one() + TIA::one();
+    PrivacyRelation::new_all(
+        // relation
+        move |&d_in: &TIA, &eps: &TIA| {
+            if d_in.is_sign_negative() {
+                return fallible!(InvalidDistance, "selection mechanism: input sensitivity must be non-negative")
+            }
+            if eps.is_sign_negative() {
+                return fallible!(InvalidDistance, "selection mechanism: epsilon must be non-negative")
+            }
+            Ok(eps >= _2 * d_in / scale)
+        },
+        // forward map
+        Some(move |&d_in: &TIA| Ok(Box::new(_2 * d_in / scale))),
+        // backward map
+        Some(move |&eps: &TIA| Ok(Box::new(eps * scale / _2))))
+}
+
+/// Make a Measurement that privately selects the index of the largest score in a vector of candidate scores.
+///
+/// Each score is perturbed with `noise` scaled by `scale`, and the index of the largest noisy score is released.
+/// With Gumbel noise this is the exponential mechanism, and with exponential noise this is report-noisy-max.
+/// The relation holds whenever epsilon >= 2 * d_in / scale, where d_in bounds the change in any one score.
+pub fn make_base_exponential_candidates<TIA, TO>(
+    scale: TIA, noise: SelectionNoise
+) -> Fallible<Measurement<VectorDomain<AllDomain<TIA>>, AllDomain<TO>, LInfDistance<TIA>, MaxDivergence<TIA>>>
+    where TIA: 'static + Float + SampleUniform + TotalOrd + CheckNull,
+          TO: ExactIntCast<usize> + CheckNull {
+    if scale.is_sign_negative() || scale.is_zero() {
+        return fallible!(MakeMeasurement, "scale must be positive")
+    }
+
+    Ok(Measurement::new(
+        VectorDomain::new_all(),
+        AllDomain::new(),
+        Function::new_fallible(move |scores: &Vec<TIA>| {
+            let mut best: Option<(usize, TIA)> = None;
+            for (i, &score) in scores.iter().enumerate() {
+                let noisy = score + scale * noise.sample::<TIA>()?;
+                let is_best = match best {
+                    Some((_, max)) => noisy.total_cmp(&max)? == Ordering::Greater,
+                    None => true
+                };
+                if is_best { best = Some((i, noisy)) }
+            }
+            let (index, _) = best.ok_or_else(|| err!(FailedFunction, "there must be at least one candidate"))?;
+            TO::exact_int_cast(index)
+        }),
+        LInfDistance::default(),
+        MaxDivergence::default(),
+        make_selection_privacy_relation(scale)))
+}
+
+/// Make a Measurement that privately selects the index of the largest score with the permute-and-flip mechanism of
+/// [McKenna and Sheldon](https://arxiv.org/abs/2010.12603).
+///
+/// Candidates are visited in a uniformly random order, and each is released with probability exp((score - max) / scale).
+/// The accept/reject steps are exact Bernoulli trials, and the candidate with the largest score is always accepted.
+/// The expected utility is never worse than that of the exponential mechanism.
+/// The relation holds whenever epsilon >= 2 * d_in / scale, where d_in bounds the change in any one score.
+pub fn make_base_permute_and_flip<TIA, TO>(
+    scale: TIA
+) -> Fallible<Measurement<VectorDomain<AllDomain<TIA>>, AllDomain<TO>, LInfDistance<TIA>, MaxDivergence<TIA>>>
+    where TIA: 'static + Float + TotalOrd + CheckNull,
+          TO: ExactIntCast<usize> + CheckNull,
+          f64: From<TIA> {
+    if scale.is_sign_negative() || scale.is_zero() {
+        return fallible!(MakeMeasurement, "scale must be positive")
+    }
+    let scale_rational = BigRational::from_float(f64::from(scale))
+        .ok_or_else(|| err!(MakeMeasurement, "scale must be finite"))?;
+    let to_rational = |score: TIA| BigRational::from_float(f64::from(score))
+        .ok_or_else(|| err!(FailedFunction, "scores must be finite"));
+
+    Ok(Measurement::new(
+        VectorDomain::new_all(),
+        AllDomain::new(),
+        Function::new_fallible(move |scores: &Vec<TIA>| {
+            let max = scores.iter().cloned().map(Ok)
+                .reduce(|l, r| l?.total_max(r?))
+                .ok_or_else(|| err!(FailedFunction, "there must be at least one candidate"))??;
+            let max = to_rational(max)?;
+
+            // lazily draw a uniformly random permutation with a partial Fisher-Yates shuffle
+            let mut order: Vec<usize> = (0..scores.len()).collect();
+            for i in 0..order.len() {
+                let j = i + usize::sample_uniform_int_0_u(order.len() - i)?;
+                order.swap(i, j);
+                let candidate = order[i];
+                // gamma = (max - score) / scale is computed exactly, so that the acceptance probability is exact
+                let gamma = (&max - to_rational(scores[candidate])?) / &scale_rational;
+                if sample_bernoulli_exp(&gamma)? {
+                    return TO::exact_int_cast(candidate)
+                }
+            }
+            fallible!(FailedFunction, "permute-and-flip failed to accept the candidate with the largest score")
+        }),
+        LInfDistance::default(),
+        MaxDivergence::default(),
+        make_selection_privacy_relation(scale)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_exponential_candidates() -> Fallible<()> {
+        for noise in [SelectionNoise::Gumbel, SelectionNoise::Exponential] {
+            let measurement = make_base_exponential_candidates::<f64, u32>(0.1, noise)?;
+            assert_eq!(measurement.invoke(&vec![1., 2., 100., 3.])?, 2);
+            assert!(measurement.invoke(&vec![]).is_err());
+
+            assert!(measurement.check(&1., &20.)?);
+            assert!(!measurement.check(&1., &19.9)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_permute_and_flip() -> Fallible<()> {
+        let measurement = make_base_permute_and_flip::<f64, u32>(0.1)?;
+        assert_eq!(measurement.invoke(&vec![1., 2., 100., 3.])?, 2);
+        assert!(measurement.invoke(&vec![]).is_err());
+        assert!(measurement.invoke(&vec![1., f64::NAN]).is_err());
+        assert!(make_base_permute_and_flip::<f64, u32>(f64::INFINITY).is_err());
+
+        assert!(measurement.check(&1., &20.)?);
+        assert!(!measurement.check(&1., &19.9)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_selection_noise_from_str() -> Fallible<()> {
+        assert_eq!(SelectionNoise::from_str("Gumbel")?, SelectionNoise::Gumbel);
+        assert_eq!(SelectionNoise::from_str("exponential")?, SelectionNoise::Exponential);
+        assert!(SelectionNoise::from_str("laplace").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,288 @@
+use std::hash::Hash;
+
+use num::Float;
+
+use crate::core::{Function, Measurement};
+use crate::dist::{DiscreteDistance, MaxDivergence};
+use crate::dom::{AllDomain, VectorDomain};
+use crate::error::*;
+use crate::meas::randomized_response::{ln_1p_up, make_local_privacy_relation, randomized_response_epsilon};
+use crate::samplers::{SampleBernoulli, SampleUniformInt};
+use crate::traits::{CheckNull, ExactIntCast};
+
+/// The Mersenne prime 2^61 - 1, used as the modulus of the universal hash family.
+const HASH_MODULUS: u64 = (1 << 61) - 1;
+
+fn check_epsilon<Q: Float>(epsilon: Q) -> Fallible<()> {
+    if !(epsilon.is_finite() && epsilon > Q::zero()) {
+        return fallible!(MakeMeasurement, "epsilon must be positive and finite")
+    }
+    Ok(())
+}
+
+/// Probability that a zero bit is reported as one in optimized unary encoding.
+/// A one bit is reported as one with probability 1/2.
+fn unary_encoding_flip_prob<Q: Float>(epsilon: Q) -> Q {
+    (epsilon.exp() + Q::one()).recip()
+}
+
+/// Make a Measurement that one-hot encodes a category and reports each bit with optimized unary encoding (OUE).
+/// The bit of the held category is kept with probability 1/2,
+/// and every other bit is set with probability 1 / (exp(`epsilon`) + 1).
+/// Inputs outside of `categories` are encoded as all zeros.
+///
+/// The relation holds whenever the privacy loss is at least ln((1 - q) / q), where q is the bit flip probability
+/// as sampled, rounded up to cover floating-point error. This is within a few ulps of `epsilon`.
+pub fn make_optimized_unary_encoding<T, Q>(
+    categories: Vec<T>, epsilon: Q, constant_time: bool
+) -> Fallible<Measurement<AllDomain<T>, VectorDomain<AllDomain<bool>>, DiscreteDistance, MaxDivergence<Q>>>
+    where T: 'static + Eq + CheckNull,
+          Q: 'static + Float,
+          f64: From<Q> {
+    if categories.is_empty() {
+        return fallible!(MakeMeasurement, "there must be at least one category")
+    }
+    check_epsilon(epsilon)?;
+    let flip_prob = unary_encoding_flip_prob(epsilon);
+    // the privacy loss of the flip probability as sampled: ln(1 + (1 - 2q) / q)
+    let epsilon = ln_1p_up(flip_prob.mul_add(-(Q::one() + Q::one()), Q::one()) / flip_prob);
+    let flip_prob = f64::from(flip_prob);
+
+    Ok(Measurement::new(
+        AllDomain::new(),
+        VectorDomain::new_all(),
+        Function::new_fallible(move |arg: &T| categories.iter()
+            .map(|cat| bool::sample_bernoulli(if cat == arg { 0.5 } else { flip_prob }, constant_time))
+            .collect()),
+        DiscreteDistance,
+        MaxDivergence::default(),
+        make_local_privacy_relation(epsilon)))
+}
+
+/// Make a post-processor that estimates the number of clients holding each category from optimized unary encoding reports.
+///
+/// The estimates are unbiased: (c - n * q) / (1/2 - q), where c is the number of reports with the category's bit set,
+/// n is the number of reports, and q = 1 / (exp(`epsilon`) + 1).
+pub fn make_optimized_unary_encoding_estimator<Q>(
+    num_categories: usize, epsilon: Q
+) -> Fallible<Function<VectorDomain<VectorDomain<AllDomain<bool>>>, VectorDomain<AllDomain<Q>>>>
+    where Q: 'static + Float + ExactIntCast<usize> + CheckNull {
+    if num_categories == 0 {
+        return fallible!(MakeTransformation, "there must be at least one category")
+    }
+    check_epsilon(epsilon)?;
+    let flip_prob = unary_encoding_flip_prob(epsilon);
+    let keep_prob = (Q::one() + Q::one()).recip();
+
+    Ok(Function::new_fallible(move |reports: &Vec<Vec<bool>>| {
+        let mut counts = vec![0usize; num_categories];
+        for report in reports {
+            if report.len() != num_categories {
+                return fallible!(FailedFunction, "each report must have one bit per category")
+            }
+            counts.iter_mut().zip(report).filter(|(_, bit)| **bit).for_each(|(count, _)| *count += 1);
+        }
+        let num_reports = Q::exact_int_cast(reports.len())?;
+        counts.into_iter()
+            .map(|count| Ok((Q::exact_int_cast(count)? - num_reports * flip_prob) / (keep_prob - flip_prob)))
+            .collect()
+    }))
+}
+
+/// A hash function drawn from the universal family h(x) = ((a * x + b) mod p) mod `range`, where p = 2^61 - 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalHash {
+    a: u64,
+    b: u64,
+    range: usize,
+}
+
+impl LocalHash {
+    /// Construct the hash function with coefficients `a` and `b` into [0, `range`),
+    /// as when rebuilding a report received from a client.
+    pub fn new(a: u64, b: u64, range: usize) -> Fallible<Self> {
+        if a == 0 || a >= HASH_MODULUS {
+            return fallible!(FailedFunction, "a must be in [1, 2^61 - 1)")
+        }
+        if b >= HASH_MODULUS {
+            return fallible!(FailedFunction, "b must be in [0, 2^61 - 1)")
+        }
+        if range == 0 {
+            return fallible!(FailedFunction, "range must be positive")
+        }
+        Ok(LocalHash { a, b, range })
+    }
+
+    /// Draw a hash function into [0, `range`) uniformly from the family.
+    fn sample(range: usize) -> Fallible<Self> {
+        Ok(LocalHash {
+            a: u64::sample_uniform_int_0_u(HASH_MODULUS - 1)? + 1,
+            b: u64::sample_uniform_int_0_u(HASH_MODULUS)?,
+            range,
+        })
+    }
+
+    pub fn a(&self) -> u64 { self.a }
+    pub fn b(&self) -> u64 { self.b }
+    pub fn range(&self) -> usize { self.range }
+
+    /// Hash the index of a category.
+    pub fn hash(&self, index: usize) -> usize {
+        let x = (self.a as u128 * index as u128 + self.b as u128) % HASH_MODULUS as u128;
+        (x % self.range as u128) as usize
+    }
+}
+
+/// A client report from optimized local hashing: the sampled hash function and the randomized hash value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalHashReport {
+    pub hash: LocalHash,
+    pub value: usize,
+}
+
+impl CheckNull for LocalHashReport {
+    fn is_null(&self) -> bool { false }
+}
+
+/// Size of the hash range in optimized local hashing: round(exp(`epsilon`)) + 1.
+fn local_hashing_range<Q: Float>(epsilon: Q) -> Fallible<usize> {
+    (epsilon.exp().round() + Q::one()).to_usize()
+        .ok_or_else(|| err!(MakeMeasurement, "epsilon is too large to size the hash range"))
+}
+
+/// Probability that optimized local hashing reports the true hash value.
+fn local_hashing_keep_prob<Q: Float + ExactIntCast<usize>>(epsilon: Q, range: usize) -> Fallible<Q> {
+    let exp_eps = epsilon.exp();
+    Ok(exp_eps / (exp_eps + Q::exact_int_cast(range)? - Q::one()))
+}
+
+/// Make a Measurement that reports a category with optimized local hashing (OLH).
+/// Each report carries a freshly sampled hash function h into g = round(exp(`epsilon`)) + 1 buckets,
+/// and the bucket h(x) of the held category, randomized over the g buckets with k-ary randomized response.
+/// Inputs outside of `categories` are hashed as an additional category.
+///
+/// The relation holds whenever the privacy loss is at least that of g-ary randomized response
+/// with the keep probability as sampled, rounded up to cover floating-point error. This is within a few ulps of `epsilon`.
+pub fn make_optimized_local_hashing<T, Q>(
+    categories: Vec<T>, epsilon: Q, constant_time: bool
+) -> Fallible<Measurement<AllDomain<T>, AllDomain<LocalHashReport>, DiscreteDistance, MaxDivergence<Q>>>
+    where T: 'static + Eq + Hash + CheckNull,
+          Q: 'static + Float + ExactIntCast<usize>,
+          f64: From<Q> {
+    if categories.is_empty() {
+        return fallible!(MakeMeasurement, "there must be at least one category")
+    }
+    check_epsilon(epsilon)?;
+    let range = local_hashing_range(epsilon)?;
+    let keep_prob = local_hashing_keep_prob(epsilon, range)?;
+    let epsilon = randomized_response_epsilon(keep_prob, Q::exact_int_cast(range)?);
+    let keep_prob = f64::from(keep_prob);
+
+    Ok(Measurement::new(
+        AllDomain::new(),
+        AllDomain::new(),
+        Function::new_fallible(move |arg: &T| {
+            let index = categories.iter().position(|cat| cat == arg).unwrap_or(categories.len());
+            let hash = LocalHash::sample(range)?;
+            let mut value = hash.hash(index);
+            if !bool::sample_bernoulli(keep_prob, constant_time)? {
+                // choose uniformly among the other buckets
+                let other = usize::sample_uniform_int_0_u(range - 1)?;
+                value = if other >= value { other + 1 } else { other };
+            }
+            Ok(LocalHashReport { hash, value })
+        }),
+        DiscreteDistance,
+        MaxDivergence::default(),
+        make_local_privacy_relation(epsilon)))
+}
+
+/// Make a post-processor that estimates the number of clients holding each category from optimized local hashing reports.
+///
+/// The estimates are unbiased: (c - n / g) / (p - 1 / g), where c is the number of reports whose hash of the category
+/// matches the reported value, n is the number of reports, g is the size of the hash range,
+/// and p is the probability of reporting the true hash value.
+pub fn make_optimized_local_hashing_estimator<Q>(
+    num_categories: usize, epsilon: Q
+) -> Fallible<Function<VectorDomain<AllDomain<LocalHashReport>>, VectorDomain<AllDomain<Q>>>>
+    where Q: 'static + Float + ExactIntCast<usize> + CheckNull {
+    if num_categories == 0 {
+        return fallible!(MakeTransformation, "there must be at least one category")
+    }
+    check_epsilon(epsilon)?;
+    let range = local_hashing_range(epsilon)?;
+    let keep_prob = local_hashing_keep_prob(epsilon, range)?;
+    let collision_prob = Q::exact_int_cast(range)?.recip();
+
+    Ok(Function::new_fallible(move |reports: &Vec<LocalHashReport>| {
+        if reports.iter().any(|report| report.hash.range != range) {
+            return fallible!(FailedFunction, "reports must be hashed into {} buckets", range)
+        }
+        let num_reports = Q::exact_int_cast(reports.len())?;
+        (0..num_categories).map(|index| {
+            let support = reports.iter().filter(|report| report.hash.hash(index) == report.value).count();
+            Ok((Q::exact_int_cast(support)? - num_reports * collision_prob) / (keep_prob - collision_prob))
+        }).collect()
+    }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_optimized_unary_encoding() -> Fallible<()> {
+        let categories = vec!["A", "B", "C"];
+        let measurement = make_optimized_unary_encoding(categories, 1., false)?;
+        assert_eq!(measurement.invoke(&"B")?.len(), 3);
+        assert_eq!(measurement.invoke(&"not a category")?.len(), 3);
+        assert!(measurement.check(&1, &1.000001)?);
+        assert!(!measurement.check(&1, &0.999)?);
+
+        assert!(make_optimized_unary_encoding(vec!["A"], 0., false).is_err());
+        assert!(make_optimized_unary_encoding(Vec::<i32>::new(), 1., false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_optimized_local_hashing() -> Fallible<()> {
+        let categories = vec!["A", "B", "C"];
+        let measurement = make_optimized_local_hashing(categories, 2., false)?;
+        // exp(2) rounds to 7, so there are 8 buckets
+        let report = measurement.invoke(&"B")?;
+        assert_eq!(report.hash.range, 8);
+        assert!(report.value < 8);
+        assert!(measurement.check(&1, &2.000001)?);
+        assert!(!measurement.check(&1, &1.999)?);
+
+        // a server can rebuild the report from its parts
+        let hash = LocalHash::new(report.hash.a(), report.hash.b(), report.hash.range())?;
+        assert_eq!(LocalHashReport { hash, value: report.value }, report);
+        assert!(LocalHash::new(0, 1, 8).is_err());
+        assert!(LocalHash::new(1, HASH_MODULUS, 8).is_err());
+        assert!(LocalHash::new(1, 1, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_frequency_oracle_estimators() -> Fallible<()> {
+        let categories = vec![0, 1, 2, 3];
+        let truth = (0..4000).map(|i| if i % 4 == 0 { 0 } else { 1 }).collect::<Vec<i32>>();
+
+        let measurement = make_optimized_unary_encoding(categories.clone(), 2., false)?;
+        let reports = truth.iter().map(|v| measurement.invoke(v)).collect::<Fallible<Vec<_>>>()?;
+        let estimates = make_optimized_unary_encoding_estimator(4, 2.)?.eval(&reports)?;
+        assert!((estimates[0] - 1000.).abs() < 300., "{:?}", estimates);
+        assert!((estimates[1] - 3000.).abs() < 300., "{:?}", estimates);
+        assert!(estimates[3].abs() < 300., "{:?}", estimates);
+
+        let measurement = make_optimized_local_hashing(categories, 2., false)?;
+        let reports = truth.iter().map(|v| measurement.invoke(v)).collect::<Fallible<Vec<_>>>()?;
+        let estimates = make_optimized_local_hashing_estimator(4, 2.)?.eval(&reports)?;
+        assert!((estimates[0] - 1000.).abs() < 300., "{:?}", estimates);
+        assert!((estimates[1] - 3000.).abs() < 300., "{:?}", estimates);
+        assert!(estimates[3].abs() < 300., "{:?}", estimates);
+        Ok(())
+    }
+}
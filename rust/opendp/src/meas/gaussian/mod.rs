@@ -1,9 +1,12 @@
+use std::fmt::Debug;
+
 use num::Float;
 
-use crate::core::{Function, Measurement, PrivacyRelation, Domain, SensitivityMetric};
-use crate::dist::{L2Distance, SmoothedMaxDivergence, AbsoluteDistance};
+use crate::core::{Function, Measurement, PrivacyRelation, Domain, Measure, SensitivityMetric};
+use crate::dist::{L2Distance, SmoothedMaxDivergence, AbsoluteDistance, ZeroConcentratedDivergence, RenyiDivergence, PrivacyLossDivergence};
 use crate::dom::{AllDomain, VectorDomain};
 use crate::error::*;
+use crate::pld::{check_discretization, make_pld_privacy_relation, PrivacyLossDistribution};
 use crate::samplers::SampleGaussian;
 use crate::traits::{InfCast, CheckNull};
 
@@ -32,6 +35,63 @@ fn make_gaussian_privacy_relation<T, MI>(scale: T) -> PrivacyRelation<MI, Smooth
     })
 }
 
+fn make_gaussian_zcdp_privacy_relation<T, MI>(scale: T) -> PrivacyRelation<MI, ZeroConcentratedDivergence<T>>
+    where T: 'static + Clone + Float + InfCast<f64>,
+          MI: SensitivityMetric<Distance=T> {
+    PrivacyRelation::new_all(
+        // relation
+        move |&d_in: &T, &rho: &T| {
+            if d_in.is_sign_negative() {
+                return fallible!(InvalidDistance, "gaussian mechanism: input sensitivity must be non-negative")
+            }
+            if rho.is_sign_negative() {
+                return fallible!(InvalidDistance, "gaussian mechanism: rho must be non-negative")
+            }
+            if d_in.is_zero() { return Ok(true) }
+            let _2 = T::inf_cast(2.)?;
+            Ok(rho >= (d_in / scale).powi(2) / _2)
+        },
+        // forward map
+        Some(move |&d_in: &T| {
+            let _2 = T::inf_cast(2.)?;
+            Ok(Box::new((d_in / scale).powi(2) / _2))
+        }),
+        // backward map
+        Some(move |&rho: &T| {
+            let _2 = T::inf_cast(2.)?;
+            Ok(Box::new(scale * (_2 * rho).sqrt()))
+        }))
+}
+
+fn make_gaussian_rdp_privacy_relation<T, MI>(scale: T, measure: RenyiDivergence<T>) -> PrivacyRelation<MI, RenyiDivergence<T>>
+    where T: 'static + Clone + Float + InfCast<f64> + Debug,
+          MI: SensitivityMetric<Distance=T> {
+    PrivacyRelation::new_all(
+        // relation
+        enclose!(measure, move |&d_in: &T, curve: &Vec<T>| {
+            if d_in.is_sign_negative() {
+                return fallible!(InvalidDistance, "gaussian mechanism: input sensitivity must be non-negative")
+            }
+            measure.check_curve(curve)?;
+            let _2 = T::inf_cast(2.)?;
+            let rho = (d_in / scale).powi(2) / _2;
+            Ok(measure.alphas().iter().zip(curve.iter()).all(|(&alpha, &epsilon)| epsilon >= alpha * rho))
+        }),
+        // forward map
+        Some(enclose!(measure, move |&d_in: &T| {
+            let _2 = T::inf_cast(2.)?;
+            let rho = (d_in / scale).powi(2) / _2;
+            Ok(Box::new(measure.alphas().iter().map(|&alpha| alpha * rho).collect()))
+        })),
+        // backward map
+        Some(move |curve: &Vec<T>| {
+            measure.check_curve(curve)?;
+            let _2 = T::inf_cast(2.)?;
+            Ok(Box::new(measure.alphas().iter().zip(curve.iter())
+                .map(|(&alpha, &epsilon)| scale * (_2 * epsilon / alpha).sqrt())
+                .fold(T::infinity(), T::min)))
+        }))
+}
 
 pub trait GaussianDomain: Domain {
     type Metric: SensitivityMetric<Distance=Self::Atom> + Default;
@@ -66,9 +126,12 @@ impl<T> GaussianDomain for VectorDomain<AllDomain<T>>
 }
 
 
-pub fn make_base_gaussian<D>(scale: D::Atom) -> Fallible<Measurement<D, D, D::Metric, SmoothedMaxDivergence<D::Atom>>>
+fn make_gaussian<D, MO>(
+    scale: D::Atom, output_measure: MO, privacy_relation: impl FnOnce(D::Atom) -> PrivacyRelation<D::Metric, MO>
+) -> Fallible<Measurement<D, D, D::Metric, MO>>
     where D: GaussianDomain,
-          D::Atom: 'static + Clone + SampleGaussian + Float + InfCast<f64> + CheckNull {
+          D::Atom: 'static + Clone + SampleGaussian + Float + InfCast<f64> + CheckNull,
+          MO: Measure {
     if scale.is_sign_negative() {
         return fallible!(MakeMeasurement, "scale must not be negative")
     }
@@ -77,14 +140,47 @@ pub fn make_base_gaussian<D>(scale: D::Atom) -> Fallible<Measurement<D, D, D::Me
         D::new(),
         D::noise_function(scale.clone()),
         D::Metric::default(),
-        SmoothedMaxDivergence::default(),
-        make_gaussian_privacy_relation(scale),
+        output_measure,
+        privacy_relation(scale),
     ))
 }
 
+pub fn make_base_gaussian<D>(scale: D::Atom) -> Fallible<Measurement<D, D, D::Metric, SmoothedMaxDivergence<D::Atom>>>
+    where D: GaussianDomain,
+          D::Atom: 'static + Clone + SampleGaussian + Float + InfCast<f64> + CheckNull {
+    make_gaussian(scale, SmoothedMaxDivergence::default(), make_gaussian_privacy_relation)
+}
+
+/// Make a Measurement that adds gaussian noise, with a privacy relation expressed under zero-concentrated DP.
+/// The relation holds whenever rho >= (d_in / scale)^2 / 2.
+pub fn make_base_gaussian_zcdp<D>(scale: D::Atom) -> Fallible<Measurement<D, D, D::Metric, ZeroConcentratedDivergence<D::Atom>>>
+    where D: GaussianDomain,
+          D::Atom: 'static + Clone + SampleGaussian + Float + InfCast<f64> + CheckNull {
+    make_gaussian(scale, ZeroConcentratedDivergence::default(), make_gaussian_zcdp_privacy_relation)
+}
+
+/// Make a Measurement that adds gaussian noise, with a privacy relation expressed under Rényi DP at each order in `alphas`.
+/// The relation holds whenever epsilon(alpha) >= alpha * (d_in / scale)^2 / 2 at every order.
+pub fn make_base_gaussian_rdp<D>(scale: D::Atom, alphas: Vec<D::Atom>) -> Fallible<Measurement<D, D, D::Metric, RenyiDivergence<D::Atom>>>
+    where D: GaussianDomain,
+          D::Atom: 'static + Clone + SampleGaussian + Float + InfCast<f64> + CheckNull + Debug {
+    let output_measure = RenyiDivergence::new(alphas)?;
+    make_gaussian(scale, output_measure.clone(), |scale| make_gaussian_rdp_privacy_relation(scale, output_measure))
+}
+
+/// Make a Measurement that adds gaussian noise, with a privacy relation expressed by its privacy loss distribution.
+/// The distribution is discretized onto a grid with spacing `discretization`.
+pub fn make_base_gaussian_pld<D>(scale: f64, discretization: f64) -> Fallible<Measurement<D, D, D::Metric, PrivacyLossDivergence>>
+    where D: GaussianDomain<Atom=f64>, D::Metric: 'static {
+    check_discretization(discretization)?;
+    make_gaussian(scale, PrivacyLossDivergence, |scale| make_pld_privacy_relation(
+        move |d_in: &f64| PrivacyLossDistribution::new_gaussian(scale, *d_in, discretization)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trans::make_bounded_sum;
 
     #[test]
     fn test_make_gaussian_mechanism() -> Fallible<()> {
@@ -105,4 +201,66 @@ mod tests {
         assert!(measurement.check(&0.1, &(0.5, 0.00001))?);
         Ok(())
     }
+
+    #[test]
+    fn test_make_gaussian_pld_mechanism() -> Fallible<()> {
+        let measurement = make_base_gaussian_pld::<VectorDomain<_>>(2.0, 1e-3)?;
+        let _ret = measurement.invoke(&vec![0.0, 1.0])?;
+
+        let pld = PrivacyLossDistribution::new_gaussian(2.0, 1.0, 1e-3)?;
+        assert!(measurement.check(&1.0, &pld)?);
+        assert!(!measurement.check(&2.0, &pld)?);
+        assert_eq!(measurement.privacy_relation.forward_map.as_ref().unwrap_test()(&1.0)?, Box::new(pld));
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_gaussian_zcdp_mechanism() -> Fallible<()> {
+        let measurement = make_base_gaussian_zcdp::<AllDomain<_>>(2.0)?;
+        let _ret = measurement.invoke(&0.0)?;
+
+        // rho = (1 / 2)^2 / 2
+        assert!(measurement.check(&1.0, &0.125)?);
+        assert!(!measurement.check(&1.0, &0.124)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_chain_gaussian_zcdp() -> Fallible<()> {
+        let chain = (
+            make_bounded_sum((0., 10.))? >>
+            make_base_gaussian_zcdp(10.)?
+        )?;
+        let _ret = chain.invoke(&vec![1., 2., 3.])?;
+        // sensitivity of 10, so rho = (10 / 10)^2 / 2
+        assert!(chain.check(&1, &0.5)?);
+        assert!(!chain.check(&2, &0.5)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_gaussian_zcdp_vec_mechanism() -> Fallible<()> {
+        let measurement = make_base_gaussian_zcdp::<VectorDomain<_>>(1.0)?;
+        let _ret = measurement.invoke(&vec![0.0, 1.0])?;
+
+        assert!(measurement.check(&1.0, &0.5)?);
+        assert!(!measurement.check(&2.0, &0.5)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_gaussian_rdp_mechanism() -> Fallible<()> {
+        let measurement = make_base_gaussian_rdp::<AllDomain<_>>(2.0, vec![2., 10.])?;
+        let _ret = measurement.invoke(&0.0)?;
+
+        // epsilon(alpha) = alpha * (1 / 2)^2 / 2
+        assert!(measurement.check(&1.0, &vec![0.25, 1.25])?);
+        assert!(!measurement.check(&1.0, &vec![0.25, 1.24])?);
+        assert!(measurement.check(&1.0, &vec![0.25]).is_err());
+        assert_eq!(measurement.privacy_relation.forward_map.as_ref().unwrap_test()(&2.0)?, Box::new(vec![1., 5.]));
+        assert!(make_base_gaussian_rdp::<AllDomain<_>>(2.0, vec![1.]).is_err());
+        assert!(make_base_gaussian_rdp::<AllDomain<_>>(2.0, vec![]).is_err());
+        assert!(make_base_gaussian_rdp::<AllDomain<_>>(2.0, vec![2., 2.]).is_err());
+        Ok(())
+    }
 }
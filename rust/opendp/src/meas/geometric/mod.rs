@@ -1,9 +1,10 @@
 use crate::core::{Function, Measurement, PrivacyRelation, Domain, SensitivityMetric};
-use crate::dist::{MaxDivergence, L1Distance, AbsoluteDistance};
+use crate::dist::{MaxDivergence, L1Distance, AbsoluteDistance, PrivacyLossDivergence};
 use crate::dom::{AllDomain, VectorDomain};
 use crate::error::*;
+use crate::pld::{check_discretization, make_pld_privacy_relation, PrivacyLossDistribution};
 use crate::samplers::SampleTwoSidedGeometric;
-use num::Float;
+use num::{Float, ToPrimitive};
 use crate::traits::{DistanceConstant, InfCast, CheckNull, TotalOrd};
 
 
@@ -63,6 +64,27 @@ pub fn make_base_geometric<D, QO>(
         PrivacyRelation::new_from_constant(scale.recip())))
 }
 
+/// Make a Measurement that adds geometric noise to a scalar, with a privacy relation expressed by its privacy loss distribution.
+/// The distribution is discretized onto a grid with spacing `discretization`.
+pub fn make_base_geometric_pld<T>(
+    scale: f64, discretization: f64
+) -> Fallible<Measurement<AllDomain<T>, AllDomain<T>, AbsoluteDistance<T>, PrivacyLossDivergence>>
+    where T: 'static + Clone + SampleTwoSidedGeometric + CheckNull + ToPrimitive {
+    if scale.is_sign_negative() { return fallible!(MakeMeasurement, "scale must not be negative") }
+    check_discretization(discretization)?;
+    Ok(Measurement::new(
+        AllDomain::new(),
+        AllDomain::new(),
+        AllDomain::noise_function(scale, None),
+        AbsoluteDistance::default(),
+        PrivacyLossDivergence,
+        make_pld_privacy_relation(move |d_in: &T| {
+            let sensitivity = d_in.to_u32()
+                .ok_or_else(|| err!(InvalidDistance, "sensitivity must be a non-negative 32-bit integer"))?;
+            PrivacyLossDistribution::new_geometric(scale, sensitivity, discretization)
+        })))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,6 +109,16 @@ mod tests {
         assert!(measurement.check(&1, &0.5).unwrap_test());
     }
 
+    #[test]
+    fn test_make_geometric_pld_mechanism() -> Fallible<()> {
+        let measurement = make_base_geometric_pld::<i32>(1.0, 1e-3)?;
+        let _ret = measurement.invoke(&205)?;
+        assert!(measurement.check(&2, &PrivacyLossDistribution::new_geometric(1., 2, 1e-3)?)?);
+        assert!(!measurement.check(&2, &PrivacyLossDistribution::new_geometric(1., 1, 1e-3)?)?);
+        assert!(measurement.check(&-1, &PrivacyLossDistribution::new_geometric(1., 1, 1e-3)?).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_make_geometric_mechanism() {
         let measurement = make_base_geometric::<AllDomain<_>, f64>(10.0, None).unwrap_test();
@@ -1,10 +1,11 @@
 use num::Float;
 
 use crate::core::{Measurement, Function, PrivacyRelation, Domain, SensitivityMetric};
-use crate::dist::{L1Distance, MaxDivergence, AbsoluteDistance};
+use crate::dist::{L1Distance, MaxDivergence, AbsoluteDistance, PrivacyLossDivergence};
 use crate::dom::{AllDomain, VectorDomain};
 use crate::samplers::{SampleLaplace};
 use crate::error::*;
+use crate::pld::{check_discretization, make_pld_privacy_relation, PrivacyLossDistribution};
 use crate::traits::{InfCast, CheckNull, TotalOrd};
 
 pub trait LaplaceDomain: Domain {
@@ -55,6 +56,25 @@ pub fn make_base_laplace<D>(scale: D::Atom) -> Fallible<Measurement<D, D, D::Met
 }
 
 
+/// Make a Measurement that adds laplace noise to a scalar, with a privacy relation expressed by its privacy loss distribution.
+/// The distribution is discretized onto a grid with spacing `discretization`.
+pub fn make_base_laplace_pld(
+    scale: f64, discretization: f64
+) -> Fallible<Measurement<AllDomain<f64>, AllDomain<f64>, AbsoluteDistance<f64>, PrivacyLossDivergence>> {
+    if scale.is_sign_negative() {
+        return fallible!(MakeMeasurement, "scale must not be negative")
+    }
+    check_discretization(discretization)?;
+    Ok(Measurement::new(
+        AllDomain::new(),
+        AllDomain::new(),
+        AllDomain::noise_function(scale),
+        AbsoluteDistance::default(),
+        PrivacyLossDivergence,
+        make_pld_privacy_relation(move |d_in: &f64| PrivacyLossDistribution::new_laplace(scale, *d_in, discretization))
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,6 +100,15 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_make_laplace_pld_mechanism() -> Fallible<()> {
+        let measurement = make_base_laplace_pld(1.0, 1e-3)?;
+        let _ret = measurement.invoke(&0.0)?;
+        assert!(measurement.check(&1., &PrivacyLossDistribution::new_laplace(1., 1., 1e-3)?)?);
+        assert!(!measurement.check(&1., &PrivacyLossDistribution::new_laplace(2., 1., 1e-3)?)?);
+        Ok(())
+    }
+
     #[test]
     fn test_make_vector_laplace_mechanism() -> Fallible<()> {
         let measurement = make_base_laplace::<VectorDomain<_>>(1.0)?;
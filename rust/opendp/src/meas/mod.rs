@@ -18,6 +18,36 @@ pub mod geometric;
 #[cfg(feature="contrib")]
 pub use crate::meas::geometric::*;
 
+#[cfg(feature="contrib")]
+pub mod discrete_gaussian;
+#[cfg(feature="contrib")]
+pub use crate::meas::discrete_gaussian::*;
+
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub mod exponential;
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub use crate::meas::exponential::*;
+
+#[cfg(feature="contrib")]
+pub mod frequency_oracle;
+#[cfg(feature="contrib")]
+pub use crate::meas::frequency_oracle::*;
+
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub mod quantile;
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub use crate::meas::quantile::*;
+
+#[cfg(feature="contrib")]
+pub mod randomized_response;
+#[cfg(feature="contrib")]
+pub use crate::meas::randomized_response::*;
+
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub mod snapping;
+#[cfg(all(feature="floating-point", feature="contrib"))]
+pub use crate::meas::snapping::*;
+
 #[cfg(all(feature="floating-point", feature="contrib"))]
 pub mod stability;
 #[cfg(all(feature="floating-point", feature="contrib"))]
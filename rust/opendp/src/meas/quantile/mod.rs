@@ -0,0 +1,204 @@
+use std::cmp::Ordering;
+
+use num::Float;
+
+use crate::core::{Function, Measurement, PrivacyRelation};
+use crate::dist::{IntDistance, MaxDivergence, SymmetricDistance};
+use crate::dom::{AllDomain, BoundedDomain, VectorDomain};
+use crate::error::*;
+use crate::meas::SelectionNoise;
+use crate::samplers::SampleUniform;
+use crate::traits::{CheckNull, ExactIntCast, InfCast, TotalOrd};
+
+fn check_quantile_args<T: Float>(bounds: (T, T), scale: T) -> Fallible<()> {
+    let (lower, upper) = bounds;
+    if !(lower.is_finite() && upper.is_finite() && lower < upper) {
+        return fallible!(MakeMeasurement, "bounds must be finite, and lower must be smaller than upper")
+    }
+    if scale.is_sign_negative() || scale.is_zero() {
+        return fallible!(MakeMeasurement, "scale must be positive")
+    }
+    Ok(())
+}
+
+/// Sample the `alpha`-quantile of `data` within `bounds` with the exponential mechanism.
+fn sample_quantile<T>(data: &[T], bounds: (T, T), alpha: T, scale: T) -> Fallible<T>
+    where T: Float + SampleUniform + TotalOrd + ExactIntCast<usize> {
+    let (lower, upper) = bounds;
+    if lower == upper { return Ok(lower) }
+
+    let mut points = data.iter()
+        .map(|v| v.total_clamp(lower, upper))
+        .collect::<Fallible<Vec<T>>>()?;
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    points.insert(0, lower);
+    points.push(upper);
+
+    let target = alpha * T::exact_int_cast(data.len())?;
+    // exponential mechanism over the intervals, sampled with the gumbel-max trick
+    let mut best: Option<(usize, T)> = None;
+    for (i, window) in points.windows(2).enumerate() {
+        let width = window[1] - window[0];
+        if width.is_zero() { continue }
+        let utility = -(T::exact_int_cast(i)? - target).abs();
+        let noisy = utility / scale + width.ln() + SelectionNoise::Gumbel.sample::<T>()?;
+        let is_best = match best {
+            Some((_, max)) => noisy.total_cmp(&max)? == Ordering::Greater,
+            None => true
+        };
+        if is_best { best = Some((i, noisy)) }
+    }
+    let (index, _) = best.ok_or_else(|| err!(FailedFunction, "the bounded domain has no width"))?;
+
+    let (left, right) = (points[index], points[index + 1]);
+    (left + (right - left) * T::sample_standard_uniform(false)?).total_clamp(left, right)
+}
+
+/// Make a privacy relation for `num_levels` adaptive rounds of quantile releases on disjoint subsets of the data:
+/// epsilon >= 2 * `num_levels` * d_in / `scale`.
+fn make_quantile_privacy_relation<T>(scale: T, num_levels: T) -> PrivacyRelation<SymmetricDistance, MaxDivergence<T>>
+    where T: 'static + Float + InfCast<IntDistance> {
+    let _2 = T::one() + T::one();
+    PrivacyRelation::new_all(
+        move |&d_in: &IntDistance, &eps: &T| {
+            if eps.is_sign_negative() {
+                return fallible!(InvalidDistance, "quantile: epsilon must be non-negative")
+            }
+            Ok(eps >= _2 * num_levels * T::inf_cast(d_in)? / scale)
+        },
+        Some(move |&d_in: &IntDistance| Ok(Box::new(_2 * num_levels * T::inf_cast(d_in)? / scale))),
+        None::<fn(&_) -> _>)
+}
+
+/// Make a Measurement that privately releases the `alpha`-quantile of a dataset with the exponential mechanism.
+///
+/// The sorted data, together with the `bounds`, partition the bounded domain into intervals.
+/// An interval is chosen with probability proportional to its width times exp(-|i - `alpha` * n| / `scale`),
+/// where i is the number of records below the interval and n is the number of records,
+/// and the release is sampled uniformly from within the chosen interval.
+/// The relation holds whenever epsilon >= 2 * d_in / `scale`.
+pub fn make_quantile<T>(
+    bounds: (T, T), alpha: T, scale: T
+) -> Fallible<Measurement<VectorDomain<BoundedDomain<T>>, AllDomain<T>, SymmetricDistance, MaxDivergence<T>>>
+    where T: 'static + Float + SampleUniform + TotalOrd + CheckNull + ExactIntCast<usize> + InfCast<IntDistance> {
+    check_quantile_args(bounds, scale)?;
+    if !(alpha >= T::zero() && alpha <= T::one()) {
+        return fallible!(MakeMeasurement, "alpha must be within [0, 1]")
+    }
+
+    Ok(Measurement::new(
+        VectorDomain::new(BoundedDomain::new_closed(bounds)?),
+        AllDomain::new(),
+        Function::new_fallible(move |arg: &Vec<T>| sample_quantile(arg, bounds, alpha, scale)),
+        SymmetricDistance,
+        MaxDivergence::default(),
+        make_quantile_privacy_relation(scale, T::one())))
+}
+
+/// Recursively release the `alphas`-quantiles of `data`, splitting the data and bounds at the middle quantile.
+fn sample_quantiles<T>(data: &[T], bounds: (T, T), alphas: &[T], scale: T, release: &mut Vec<T>) -> Fallible<()>
+    where T: Float + SampleUniform + TotalOrd + ExactIntCast<usize> {
+    if alphas.is_empty() { return Ok(()) }
+    let mid = alphas.len() / 2;
+    let alpha = alphas[mid];
+    let split = sample_quantile(data, bounds, alpha, scale)?;
+
+    let (lower_data, upper_data): (Vec<T>, Vec<T>) = data.iter().partition(|v| **v < split);
+    let lower_alphas = alphas[..mid].iter().map(|a| *a / alpha).collect::<Vec<T>>();
+    let upper_alphas = alphas[mid + 1..].iter().map(|a| (*a - alpha) / (T::one() - alpha)).collect::<Vec<T>>();
+
+    sample_quantiles(&lower_data, (bounds.0, split), &lower_alphas, scale, release)?;
+    release.push(split);
+    sample_quantiles(&upper_data, (split, bounds.1), &upper_alphas, scale, release)
+}
+
+/// Make a Measurement that privately releases many quantiles of a dataset at once,
+/// with the approximate quantiles algorithm of [Kaplan, Schnapp and Stemmer](https://arxiv.org/abs/2110.05429).
+///
+/// The middle quantile is released with the exponential mechanism as in [`make_quantile`],
+/// the data and bounds are split at the release, and the remaining quantiles are released recursively on each side.
+/// Since the splits at each level of the recursion are disjoint, each record only influences
+/// one release per level, and there are ceil(log2(m + 1)) levels for m quantiles.
+/// The relation holds whenever epsilon >= 2 * ceil(log2(m + 1)) * d_in / `scale`.
+pub fn make_quantiles<T>(
+    bounds: (T, T), alphas: Vec<T>, scale: T
+) -> Fallible<Measurement<VectorDomain<BoundedDomain<T>>, VectorDomain<AllDomain<T>>, SymmetricDistance, MaxDivergence<T>>>
+    where T: 'static + Float + SampleUniform + TotalOrd + CheckNull + ExactIntCast<usize> + InfCast<IntDistance> {
+    check_quantile_args(bounds, scale)?;
+    if alphas.is_empty() {
+        return fallible!(MakeMeasurement, "there must be at least one alpha")
+    }
+    if !alphas.iter().all(|alpha| *alpha >= T::zero() && *alpha <= T::one()) {
+        return fallible!(MakeMeasurement, "each alpha must be within [0, 1]")
+    }
+    if !alphas.windows(2).all(|w| w[0] < w[1]) {
+        return fallible!(MakeMeasurement, "alphas must be strictly increasing")
+    }
+    let num_levels = T::exact_int_cast((usize::BITS - alphas.len().leading_zeros()) as usize)?;
+
+    Ok(Measurement::new(
+        VectorDomain::new(BoundedDomain::new_closed(bounds)?),
+        VectorDomain::new_all(),
+        Function::new_fallible(move |arg: &Vec<T>| {
+            let mut release = Vec::with_capacity(alphas.len());
+            sample_quantiles(arg, bounds, &alphas, scale, &mut release)?;
+            Ok(release)
+        }),
+        SymmetricDistance,
+        MaxDivergence::default(),
+        make_quantile_privacy_relation(scale, num_levels)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::trans::make_clamp;
+
+    use super::*;
+
+    #[test]
+    fn test_make_quantile() -> Fallible<()> {
+        let measurement = make_quantile((0., 100.), 0.5, 1.)?;
+        let data = (0..101).map(f64::from).collect::<Vec<_>>();
+        let median = measurement.invoke(&data)?;
+        assert!((median - 50.).abs() < 20., "{}", median);
+        assert!(measurement.check(&1, &2.)?);
+        assert!(!measurement.check(&1, &1.999)?);
+
+        assert!(make_quantile((0., 100.), 1.5, 1.).is_err());
+        assert!(make_quantile((1., 1.), 0.5, 1.).is_err());
+        assert!(make_quantile((0., 100.), 0.5, 0.).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_quantile_chain() -> Fallible<()> {
+        let bounds = (0., 10.);
+        let chain = (make_clamp(bounds)? >> make_quantile(bounds, 0.9, 0.5)?)?;
+        let data = vec![-5., 1., 2., 3., 4., 5., 6., 7., 8., 9., 50.];
+        let release = chain.invoke(&data)?;
+        assert!((0. ..=10.).contains(&release));
+        assert!(chain.check(&1, &4.)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_quantiles() -> Fallible<()> {
+        let alphas = vec![0.1, 0.25, 0.5, 0.75, 0.9];
+        let measurement = make_quantiles((0., 1000.), alphas.clone(), 1.)?;
+        let data = (0..1000).map(|i| f64::from(i) + 0.5).collect::<Vec<_>>();
+        let release = measurement.invoke(&data)?;
+        assert_eq!(release.len(), 5);
+        assert!(release.windows(2).all(|w| w[0] <= w[1]), "{:?}", release);
+        for (estimate, alpha) in release.iter().zip(alphas) {
+            assert!((estimate - alpha * 1000.).abs() < 50., "{:?}", release);
+        }
+        // five quantiles take three levels of recursion
+        assert!(measurement.check(&1, &6.)?);
+        assert!(!measurement.check(&1, &5.999)?);
+
+        assert!(make_quantiles((0., 1.), vec![0.5, 0.25], 1.).is_err());
+        assert!(make_quantiles((0., 1.), vec![], 1.).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use num::Float;
+
+use crate::core::{Function, Measurement, PrivacyRelation};
+use crate::dist::{DiscreteDistance, IntDistance, MaxDivergence, PrivacyLossDivergence};
+use crate::dom::{AllDomain, VectorDomain};
+use crate::error::*;
+use crate::pld::{check_discretization, make_pld_privacy_relation, PrivacyLossDistribution};
+use crate::samplers::{SampleBernoulli, SampleUniformInt};
+use crate::traits::{CheckNull, ExactIntCast};
+
+/// ln(1 + `x`) for a non-negative `x`, rounded up.
+///
+/// `x` may carry the error of a few floating-point roundings. Since ln(1 + x) is no more sensitive
+/// to relative error than x itself, raising the result by a relative 8 ulps covers both that error and the error of `ln_1p`.
+pub(crate) fn ln_1p_up<Q: Float>(x: Q) -> Q {
+    let _8 = Q::from(8).unwrap_assert("8 is representable in any float");
+    x.ln_1p() * (Q::one() + _8 * Q::epsilon())
+}
+
+/// The privacy loss ln(`prob` * (k - 1) / (1 - `prob`)) of k-ary randomized response, rounded up,
+/// where `prob` is the probability of a truthful answer and k is `num_categories`.
+pub(crate) fn randomized_response_epsilon<Q: Float>(prob: Q, num_categories: Q) -> Q {
+    // the ratio less one is (k * prob - 1) / (1 - prob), where the fused multiply-add rounds only once
+    ln_1p_up(prob.mul_add(num_categories, -Q::one()) / (Q::one() - prob))
+}
+
+/// Make a privacy relation for a local mechanism whose privacy loss is `epsilon` between any two inputs.
+pub(crate) fn make_local_privacy_relation<Q: 'static + Float>(epsilon: Q) -> PrivacyRelation<DiscreteDistance, MaxDivergence<Q>> {
+    PrivacyRelation::new_fallible(move |&d_in: &IntDistance, &eps: &Q| {
+        if eps.is_sign_negative() {
+            return fallible!(InvalidDistance, "randomized response: epsilon must be non-negative")
+        }
+        Ok(d_in == 0 || eps >= epsilon)
+    })
+}
+
+/// Make a Measurement that releases a boolean, answering truthfully with probability `prob` and flipping it otherwise.
+///
+/// The relation holds whenever epsilon >= ln(`prob` / (1 - `prob`)), rounded up to cover floating-point error.
+pub fn make_randomized_response_bool<Q>(
+    prob: Q, constant_time: bool
+) -> Fallible<Measurement<AllDomain<bool>, AllDomain<bool>, DiscreteDistance, MaxDivergence<Q>>>
+    where Q: 'static + Float,
+          f64: From<Q> {
+    let _2 = Q::one() + Q::one();
+    if !(prob >= _2.recip() && prob < Q::one()) {
+        return fallible!(MakeMeasurement, "probability must be within [0.5, 1)")
+    }
+    let epsilon = randomized_response_epsilon(prob, _2);
+    let prob = f64::from(prob);
+
+    Ok(Measurement::new(
+        AllDomain::new(),
+        AllDomain::new(),
+        Function::new_fallible(move |arg: &bool| {
+            Ok(*arg ^ !bool::sample_bernoulli(prob, constant_time)?)
+        }),
+        DiscreteDistance,
+        MaxDivergence::default(),
+        make_local_privacy_relation(epsilon)))
+}
+
+/// Make a Measurement that releases a category, answering truthfully with probability `prob`,
+/// and otherwise releasing one of the other `categories` uniformly at random.
+/// Inputs outside of `categories` are replaced by a category chosen uniformly at random.
+///
+/// The relation holds whenever epsilon >= ln(`prob` * (k - 1) / (1 - `prob`)), where k is the number of categories,
+/// rounded up to cover floating-point error.
+pub fn make_randomized_response<T, Q>(
+    categories: Vec<T>, prob: Q, constant_time: bool
+) -> Fallible<Measurement<AllDomain<T>, AllDomain<T>, DiscreteDistance, MaxDivergence<Q>>>
+    where T: 'static + Clone + Eq + Hash + CheckNull,
+          Q: 'static + Float + ExactIntCast<usize>,
+          f64: From<Q> {
+    check_categories(&categories)?;
+    let num_categories_q = Q::exact_int_cast(categories.len())?;
+    if !(prob >= num_categories_q.recip() && prob < Q::one()) {
+        return fallible!(MakeMeasurement, "probability must be within [1/k, 1), where k is the number of categories")
+    }
+    let epsilon = randomized_response_epsilon(prob, num_categories_q);
+
+    Ok(Measurement::new(
+        AllDomain::new(),
+        AllDomain::new(),
+        make_randomized_response_function(categories, f64::from(prob), constant_time),
+        DiscreteDistance,
+        MaxDivergence::default(),
+        make_local_privacy_relation(epsilon)))
+}
+
+/// Make a Measurement that releases a category with randomized response, like `make_randomized_response`,
+/// with a privacy relation expressed by its privacy loss distribution.
+/// The distribution is discretized onto a grid with spacing `discretization`.
+pub fn make_randomized_response_pld<T>(
+    categories: Vec<T>, prob: f64, constant_time: bool, discretization: f64
+) -> Fallible<Measurement<AllDomain<T>, AllDomain<T>, DiscreteDistance, PrivacyLossDivergence>>
+    where T: 'static + Clone + Eq + Hash + CheckNull {
+    check_categories(&categories)?;
+    let num_categories = categories.len();
+    if !(prob >= (num_categories as f64).recip() && prob < 1.) {
+        return fallible!(MakeMeasurement, "probability must be within [1/k, 1), where k is the number of categories")
+    }
+    check_discretization(discretization)?;
+
+    Ok(Measurement::new(
+        AllDomain::new(),
+        AllDomain::new(),
+        make_randomized_response_function(categories, prob, constant_time),
+        DiscreteDistance,
+        PrivacyLossDivergence,
+        make_pld_privacy_relation(move |&d_in: &IntDistance| if d_in == 0 {
+            PrivacyLossDistribution::new_identity(discretization)
+        } else {
+            PrivacyLossDistribution::new_randomized_response(prob, num_categories, discretization)
+        })))
+}
+
+fn check_categories<T: Eq + Hash>(categories: &[T]) -> Fallible<()> {
+    if categories.len() < 2 {
+        return fallible!(MakeMeasurement, "there must be at least two categories")
+    }
+    if categories.iter().collect::<HashSet<_>>().len() != categories.len() {
+        return fallible!(MakeMeasurement, "categories must be distinct")
+    }
+    Ok(())
+}
+
+/// Answer truthfully with probability `prob`, and otherwise release one of the other `categories` uniformly at random.
+fn make_randomized_response_function<T: 'static + Clone + Eq + CheckNull>(
+    categories: Vec<T>, prob: f64, constant_time: bool
+) -> Function<AllDomain<T>, AllDomain<T>> {
+    let num_categories = categories.len();
+    Function::new_fallible(move |arg: &T| {
+        let index = match categories.iter().position(|cat| cat == arg) {
+            Some(index) => {
+                if bool::sample_bernoulli(prob, constant_time)? { return Ok(arg.clone()) }
+                // choose uniformly among the other categories
+                let other = usize::sample_uniform_int_0_u(num_categories - 1)?;
+                if other >= index { other + 1 } else { other }
+            },
+            None => usize::sample_uniform_int_0_u(num_categories)?
+        };
+        Ok(categories[index].clone())
+    })
+}
+
+/// Make a post-processor that estimates the proportion of true values from randomized boolean responses.
+///
+/// The estimate is unbiased: (f - (1 - `prob`)) / (2 * `prob` - 1), where f is the observed proportion of true responses.
+pub fn make_randomized_response_bool_estimator<Q>(
+    prob: Q
+) -> Fallible<Function<VectorDomain<AllDomain<bool>>, AllDomain<Q>>>
+    where Q: 'static + Float + ExactIntCast<usize> + CheckNull {
+    let _2 = Q::one() + Q::one();
+    if !(prob > _2.recip() && prob <= Q::one()) {
+        return fallible!(MakeTransformation, "probability must be within (0.5, 1]")
+    }
+    Ok(Function::new_fallible(move |responses: &Vec<bool>| {
+        if responses.is_empty() {
+            return fallible!(FailedFunction, "there must be at least one response")
+        }
+        let num_true = Q::exact_int_cast(responses.iter().filter(|v| **v).count())?;
+        let observed = num_true / Q::exact_int_cast(responses.len())?;
+        Ok((observed - (Q::one() - prob)) / (_2 * prob - Q::one()))
+    }))
+}
+
+/// Make a post-processor that estimates the proportion of each of the `categories` from randomized categorical responses.
+///
+/// The estimates are unbiased: (f - q) / (`prob` - q), where f is the observed proportion of a category,
+/// and q = (1 - `prob`) / (k - 1) is the probability that any other category is reported as this category.
+pub fn make_randomized_response_estimator<T, Q>(
+    categories: Vec<T>, prob: Q
+) -> Fallible<Function<VectorDomain<AllDomain<T>>, VectorDomain<AllDomain<Q>>>>
+    where T: 'static + Eq + Hash + CheckNull,
+          Q: 'static + Float + ExactIntCast<usize> + CheckNull {
+    let num_categories = categories.len();
+    if num_categories < 2 {
+        return fallible!(MakeTransformation, "there must be at least two categories")
+    }
+    let num_categories_q = Q::exact_int_cast(num_categories)?;
+    if !(prob > num_categories_q.recip() && prob <= Q::one()) {
+        return fallible!(MakeTransformation, "probability must be within (1/k, 1], where k is the number of categories")
+    }
+    let other_prob = (Q::one() - prob) / (num_categories_q - Q::one());
+    Ok(Function::new_fallible(move |responses: &Vec<T>| {
+        if responses.is_empty() {
+            return fallible!(FailedFunction, "there must be at least one response")
+        }
+        let num_responses = Q::exact_int_cast(responses.len())?;
+        categories.iter().map(|cat| {
+            let observed = Q::exact_int_cast(responses.iter().filter(|v| *v == cat).count())? / num_responses;
+            Ok((observed - other_prob) / (prob - other_prob))
+        }).collect()
+    }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_randomized_response_bool() -> Fallible<()> {
+        let measurement = make_randomized_response_bool(0.75, false)?;
+        let _ret = measurement.invoke(&true)?;
+        // ln(0.75 / 0.25) = ln(3), rounded up
+        assert!(measurement.check(&1, &1.0987)?);
+        assert!(!measurement.check(&1, &1.09)?);
+        assert!(measurement.check(&0, &0.)?);
+
+        assert!(make_randomized_response_bool(0.4, false).is_err());
+        assert!(make_randomized_response_bool(1., false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_randomized_response_epsilon() {
+        // the rounded-up epsilon is never below the true privacy loss
+        assert!(randomized_response_epsilon(0.75, 2.) >= 3f64.ln());
+        assert!(randomized_response_epsilon(0.75f32, 2.) >= 3f32.ln());
+        assert!(randomized_response_epsilon(0.5, 4.) >= 3f64.ln());
+        assert!(randomized_response_epsilon(0.75, 2.) < 3f64.ln() + 1e-12);
+        assert_eq!(randomized_response_epsilon(0.5, 2.), 0.);
+    }
+
+    #[test]
+    fn test_make_randomized_response() -> Fallible<()> {
+        let categories = vec!["A", "B", "C", "D"];
+        let measurement = make_randomized_response(categories.clone(), 0.5, false)?;
+        assert!(categories.contains(&measurement.invoke(&"B")?));
+        assert!(categories.contains(&measurement.invoke(&"not a category")?));
+        // ln(0.5 * 3 / 0.5) = ln(3), rounded up
+        assert!(measurement.check(&1, &1.0987)?);
+        assert!(!measurement.check(&1, &1.09)?);
+
+        assert!(make_randomized_response(vec!["A", "A"], 0.75, false).is_err());
+        assert!(make_randomized_response(categories, 0.2, false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_randomized_response_pld() -> Fallible<()> {
+        let categories = vec!["A", "B", "C", "D"];
+        let measurement = make_randomized_response_pld(categories.clone(), 0.5, false, 1e-4)?;
+        assert!(categories.contains(&measurement.invoke(&"B")?));
+
+        // the PLD is tight at delta = 0, where epsilon = ln(3)
+        let pld = *measurement.privacy_relation.forward_map.as_ref().unwrap_test()(&1)?;
+        assert!(pld.delta(1.0987)? < 1e-4);
+        assert!(pld.delta(1.)? > 0.);
+        assert!(measurement.check(&1, &pld)?);
+        assert!(measurement.check(&0, &PrivacyLossDistribution::new_identity(1e-4)?)?);
+
+        assert!(make_randomized_response_pld(categories, 0.2, false, 1e-4).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_randomized_response_estimators() -> Fallible<()> {
+        let estimator = make_randomized_response_bool_estimator(0.75)?;
+        // 60% observed true: (0.6 - 0.25) / 0.5
+        let responses = vec![true, true, true, false, false];
+        assert!((estimator.eval(&responses)? - 0.7).abs() < 1e-12);
+
+        let categories = vec![1, 2, 3];
+        let estimator = make_randomized_response_estimator(categories.clone(), 0.5)?;
+        let estimates = estimator.eval(&vec![1, 1, 2, 3])?;
+        // q = 0.25, so (0.5 - 0.25) / 0.25 and (0.25 - 0.25) / 0.25
+        assert_eq!(estimates, vec![1., 0., 0.]);
+
+        // the estimator recovers the true proportions from many randomized responses.
+        // Each estimate has a standard deviation of at most about 0.014, so the tolerance is over 7 standard deviations
+        let measurement = make_randomized_response(categories, 0.5, false)?;
+        let truth = (0..20000).map(|i| if i % 3 == 0 { 1 } else { 2 }).collect::<Vec<i32>>();
+        let responses = truth.iter().map(|v| measurement.invoke(v)).collect::<Fallible<Vec<_>>>()?;
+        let estimates = estimator.eval(&responses)?;
+        assert!((estimates[0] - 1. / 3.).abs() < 0.1, "{:?}", estimates);
+        assert!((estimates[1] - 2. / 3.).abs() < 0.1, "{:?}", estimates);
+        assert!(estimates[2].abs() < 0.1, "{:?}", estimates);
+        Ok(())
+    }
+}
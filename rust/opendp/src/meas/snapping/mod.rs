@@ -0,0 +1,138 @@
+#[cfg(feature="use-mpfr")]
+use ieee754::Ieee754;
+#[cfg(feature="use-mpfr")]
+use num::clamp;
+
+use crate::core::Measurement;
+#[cfg(feature="use-mpfr")]
+use crate::core::{Function, PrivacyRelation};
+use crate::dist::{AbsoluteDistance, MaxDivergence};
+use crate::dom::AllDomain;
+use crate::error::*;
+#[cfg(feature="use-mpfr")]
+use crate::samplers::{SampleBernoulli, SampleUniform};
+
+/// Natural logarithm, correctly rounded by MPFR.
+#[cfg(feature="use-mpfr")]
+fn ln_rounded(value: f64) -> f64 {
+    rug::Float::with_val(f64::MANTISSA_DIGITS, value).ln().to_f64()
+}
+
+/// The smallest power of two greater than or equal to `value`.
+#[cfg(feature="use-mpfr")]
+fn next_power_of_two(value: f64) -> f64 {
+    let (_sign, exponent, mantissa) = value.decompose_raw();
+    if mantissa == 0 { value } else { f64::recompose_raw(false, exponent + 1, 0) }
+}
+
+/// Make a Measurement that releases a bounded scalar `f64` with the snapping mechanism of
+/// [Mironov](https://www.microsoft.com/en-us/research/wp-content/uploads/2012/10/lsbs.pdf).
+///
+/// The input is clamped to [-`bound`, `bound`], laplace(`scale`) noise is added,
+/// the result is rounded to the nearest multiple of the smallest power of two greater than or equal to `scale`,
+/// and the output is clamped to [-`bound`, `bound`] again.
+/// This protects against attacks on the least significant bits of floating-point laplace noise.
+///
+/// The relation holds whenever epsilon >= (d_in + 2^-49 * bound) / scale,
+/// which requires that scale < bound < 2^46 * scale.
+#[cfg(feature="use-mpfr")]
+pub fn make_base_snapping(
+    scale: f64, bound: f64
+) -> Fallible<Measurement<AllDomain<f64>, AllDomain<f64>, AbsoluteDistance<f64>, MaxDivergence<f64>>> {
+    if !scale.is_finite() || scale <= 0. {
+        return fallible!(MakeMeasurement, "scale must be positive and finite")
+    }
+    if !bound.is_finite() {
+        return fallible!(MakeMeasurement, "bound must be finite")
+    }
+    if !(scale < bound && bound < 2f64.powi(46) * scale) {
+        return fallible!(MakeMeasurement, "bound must be within (scale, 2^46 * scale)")
+    }
+    let grid = next_power_of_two(scale);
+    // the additive correction to epsilon from floating-point error in the noise
+    let correction = 2f64.powi(-49) * bound;
+
+    Ok(Measurement::new(
+        AllDomain::new(),
+        AllDomain::new(),
+        Function::new_fallible(move |arg: &f64| {
+            let arg = clamp(*arg, -bound, bound);
+            let uniform = loop {
+                let sample = f64::sample_standard_uniform(false)?;
+                if sample > 0. { break sample }
+            };
+            let magnitude = scale * ln_rounded(uniform);
+            let noised = if bool::sample_standard_bernoulli()? { arg + magnitude } else { arg - magnitude };
+            let snapped = (noised / grid).round() * grid;
+            Ok(clamp(snapped, -bound, bound))
+        }),
+        AbsoluteDistance::default(),
+        MaxDivergence::default(),
+        PrivacyRelation::new_all(
+            // relation
+            move |&d_in: &f64, &eps: &f64| {
+                if d_in.is_sign_negative() {
+                    return fallible!(InvalidDistance, "snapping mechanism: input sensitivity must be non-negative")
+                }
+                if eps.is_sign_negative() {
+                    return fallible!(InvalidDistance, "snapping mechanism: epsilon must be non-negative")
+                }
+                Ok(eps >= (d_in + correction) / scale)
+            },
+            // forward map
+            Some(move |&d_in: &f64| Ok(Box::new((d_in + correction) / scale))),
+            None::<fn(&_) -> _>)))
+}
+
+/// The snapping mechanism is only private if the logarithm in its noise is correctly rounded,
+/// so it is unavailable without MPFR.
+#[cfg(not(feature="use-mpfr"))]
+pub fn make_base_snapping(
+    _scale: f64, _bound: f64
+) -> Fallible<Measurement<AllDomain<f64>, AllDomain<f64>, AbsoluteDistance<f64>, MaxDivergence<f64>>> {
+    fallible!(MakeMeasurement, "make_base_snapping requires the use-mpfr feature for a correctly rounded logarithm")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature="use-mpfr")]
+    fn test_make_snapping_mechanism() -> Fallible<()> {
+        let measurement = make_base_snapping(1.5, 100.)?;
+        for _ in 0..100 {
+            let release = measurement.invoke(&1000.)?;
+            // outputs are clamped and lie on a grid of the next power of two
+            assert!((-100. ..=100.).contains(&release));
+            assert_eq!(release % 2., 0.);
+        }
+
+        let correction = 2f64.powi(-49) * 100.;
+        assert!(measurement.check(&1., &((1. + correction) / 1.5))?);
+        assert!(!measurement.check(&1., &(1. / 1.5))?);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature="use-mpfr")]
+    fn test_make_snapping_bad_params() {
+        assert!(make_base_snapping(0., 10.).is_err());
+        assert!(make_base_snapping(1., 0.5).is_err());
+        assert!(make_base_snapping(1., 2f64.powi(47)).is_err());
+    }
+
+    #[test]
+    #[cfg(feature="use-mpfr")]
+    fn test_next_power_of_two() {
+        assert_eq!(next_power_of_two(1.5), 2.);
+        assert_eq!(next_power_of_two(4.), 4.);
+        assert_eq!(next_power_of_two(0.3), 0.5);
+    }
+
+    #[test]
+    #[cfg(not(feature="use-mpfr"))]
+    fn test_make_snapping_requires_mpfr() {
+        assert!(make_base_snapping(1.5, 100.).is_err());
+    }
+}
@@ -0,0 +1,505 @@
+//! Privacy loss distributions (PLDs), for tight (epsilon, delta) accounting under composition.
+//!
+//! The PLD of a mechanism on a pair of neighboring datasets is the distribution of the privacy loss
+//! ln(p(y) / q(y)), where y is drawn from the output distribution p on the first dataset,
+//! and q is the output distribution on the second dataset.
+//! The PLD of a composition is the convolution of the PLDs of its members.
+//!
+//! Losses are rounded up onto a grid with spacing `discretization`,
+//! so any delta computed from a PLD is an upper bound on the true delta.
+//! The mechanisms here have a symmetric privacy loss, so a single PLD covers both orderings of the datasets.
+
+use std::convert::TryFrom;
+use std::f64::consts::{PI, SQRT_2};
+use std::rc::Rc;
+
+use num::complex::Complex64;
+use statrs::function::erf::erfc;
+
+use crate::core::{Metric, PrivacyRelation};
+use crate::dist::PrivacyLossDivergence;
+use crate::error::Fallible;
+
+/// Below this much probability, the tail of an unbounded privacy loss is truncated.
+const TAIL_MASS: f64 = 1e-15;
+
+/// Below this many multiplications, convolutions are computed directly instead of by FFT.
+const DIRECT_CONVOLUTION_LIMIT: usize = 1 << 16;
+
+/// The largest number of grid points in a PLD (128 MiB of probabilities).
+/// Finer discretizations or wider ranges of privacy loss are refused instead of exhausting memory.
+pub const MAX_GRID_LENGTH: usize = 1 << 24;
+
+/// The largest magnitude of a grid index, so that every index is exactly representable as an f64.
+const MAX_GRID_INDEX: f64 = (1u64 << 52) as f64;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrivacyLossDistribution {
+    discretization: f64,
+    /// The loss of `probs[i]` is `(offset + i) * discretization`.
+    offset: i64,
+    probs: Vec<f64>,
+    /// The probability of an infinite privacy loss.
+    infinity_mass: f64,
+}
+
+impl PrivacyLossDistribution {
+    /// Construct a PLD from `(loss, probability)` atoms.
+    ///
+    /// Atoms with a loss of negative infinity never contribute to delta, so they are dropped.
+    /// Fails if the grid spanning the finite losses would have more than [`MAX_GRID_LENGTH`] points.
+    pub fn new_from_atoms(atoms: Vec<(f64, f64)>, discretization: f64) -> Fallible<Self> {
+        check_discretization(discretization)?;
+        let mut infinity_mass = 0.;
+        let mut indexed = Vec::new();
+        for (loss, prob) in atoms {
+            if prob.is_nan() || prob.is_sign_negative() {
+                return fallible!(FailedFunction, "probabilities must be non-negative")
+            }
+            if prob == 0. || loss == f64::NEG_INFINITY { continue }
+            if loss.is_nan() {
+                return fallible!(FailedFunction, "losses must not be NaN")
+            }
+            if loss == f64::INFINITY {
+                infinity_mass += prob;
+            } else {
+                indexed.push(((loss / discretization).ceil(), prob));
+            }
+        }
+
+        let lower = indexed.iter().map(|(i, _)| *i).fold(f64::INFINITY, f64::min);
+        let upper = indexed.iter().map(|(i, _)| *i).fold(f64::NEG_INFINITY, f64::max);
+        let (offset, len) = if indexed.is_empty() { (0, 0) } else { grid_bounds(lower, upper)? };
+        let mut probs = vec![0.; len];
+        indexed.into_iter().for_each(|(i, prob)| probs[(i as i64 - offset) as usize] += prob);
+        Ok(PrivacyLossDistribution { discretization, offset, probs, infinity_mass })
+    }
+
+    /// Construct a PLD from the cumulative distribution function `cdf(loss) = P(L <= loss)`
+    /// of a privacy loss L that lies in `[lower, upper]`, except for `upper_tail` probability above `upper`.
+    ///
+    /// Any probability below `lower` is rounded up to the first grid point, and the upper tail is treated as infinite loss.
+    /// Fails if the grid from `lower` to `upper` would have more than [`MAX_GRID_LENGTH`] points.
+    pub fn new_from_cdf(
+        cdf: impl Fn(f64) -> f64, lower: f64, upper: f64, upper_tail: f64, discretization: f64,
+    ) -> Fallible<Self> {
+        check_discretization(discretization)?;
+        if !lower.is_finite() || !upper.is_finite() || lower > upper {
+            return fallible!(FailedFunction, "bounds on the privacy loss must be finite and ordered")
+        }
+        let (offset, len) = grid_bounds((lower / discretization).ceil(), (upper / discretization).ceil())?;
+        let end = offset + len as i64 - 1;
+
+        // evaluate the cdf at every grid point except the last, where the cdf is known to reach 1 - upper_tail
+        let mut cumulative = (offset..end)
+            .map(|i| cdf(i as f64 * discretization).max(0.).min(1. - upper_tail))
+            .collect::<Vec<f64>>();
+        cumulative.push(1. - upper_tail);
+
+        let probs = cumulative.iter()
+            .scan(0., |previous, &current| {
+                let prob = (current - *previous).max(0.);
+                *previous = previous.max(current);
+                Some(prob)
+            })
+            .collect();
+        Ok(PrivacyLossDistribution { discretization, offset, probs, infinity_mass: upper_tail })
+    }
+
+    /// The PLD of a mechanism that always has zero privacy loss.
+    pub fn new_identity(discretization: f64) -> Fallible<Self> {
+        Self::new_from_atoms(vec![(0., 1.)], discretization)
+    }
+
+    /// The PLD of the laplace mechanism with noise `scale`, on inputs that differ by `sensitivity`.
+    pub fn new_laplace(scale: f64, sensitivity: f64, discretization: f64) -> Fallible<Self> {
+        check_scale(scale, sensitivity)?;
+        if sensitivity == 0. { return Self::new_identity(discretization) }
+        if scale == 0. { return Self::new_from_atoms(vec![(f64::INFINITY, 1.)], discretization) }
+
+        // The loss is sensitivity / scale with probability 1/2, -sensitivity / scale with probability exp(-sensitivity / scale) / 2,
+        // and continuous in between.
+        let max_loss = sensitivity / scale;
+        Self::new_from_cdf(
+            |loss| if loss < -max_loss { 0. } else if loss >= max_loss { 1. } else {
+                ((loss - max_loss) / 2.).exp() / 2.
+            },
+            -max_loss, max_loss, 0., discretization)
+    }
+
+    /// The PLD of the gaussian mechanism with noise `scale`, on inputs that differ by `sensitivity`.
+    pub fn new_gaussian(scale: f64, sensitivity: f64, discretization: f64) -> Fallible<Self> {
+        check_scale(scale, sensitivity)?;
+        if sensitivity == 0. { return Self::new_identity(discretization) }
+        if scale == 0. { return Self::new_from_atoms(vec![(f64::INFINITY, 1.)], discretization) }
+
+        // The loss is normally distributed.
+        let std_dev = sensitivity / scale;
+        let mean = std_dev.powi(2) / 2.;
+        let normal_cdf = |z: f64| erfc(-z / SQRT_2) / 2.;
+
+        // widen the bounds until both tails are negligible
+        let mut width = 1.;
+        while normal_cdf(-width) > TAIL_MASS { width += 1. }
+
+        Self::new_from_cdf(
+            |loss| normal_cdf((loss - mean) / std_dev),
+            mean - width * std_dev, mean + width * std_dev, normal_cdf(-width), discretization)
+    }
+
+    /// The PLD of the geometric mechanism with noise `scale`, on integer inputs that differ by `sensitivity`.
+    pub fn new_geometric(scale: f64, sensitivity: u32, discretization: f64) -> Fallible<Self> {
+        check_scale(scale, sensitivity as f64)?;
+        if sensitivity == 0 { return Self::new_identity(discretization) }
+
+        // The noise is distributed as P(x) ∝ alpha^|x|, and the loss at x is (|x - sensitivity| - |x|) / scale.
+        let alpha = (-scale.recip()).exp();
+        let loss = |x: u32| (sensitivity as f64 - 2. * x as f64) / scale;
+        let mut atoms = vec![
+            // x <= 0
+            (loss(0), 1. / (1. + alpha)),
+            // x >= sensitivity
+            (loss(sensitivity), alpha.powi(sensitivity as i32) / (1. + alpha)),
+        ];
+        atoms.extend((1..sensitivity).map(|x| (loss(x), (1. - alpha) / (1. + alpha) * alpha.powi(x as i32))));
+        Self::new_from_atoms(atoms, discretization)
+    }
+
+    /// The PLD of randomized response over `categories` categories,
+    /// where the true category is reported with probability `prob`, and otherwise a uniformly random other category.
+    pub fn new_randomized_response(prob: f64, categories: usize, discretization: f64) -> Fallible<Self> {
+        if categories < 2 {
+            return fallible!(FailedFunction, "randomized response needs at least two categories")
+        }
+        let categories = categories as f64;
+        if !(categories.recip()..=1.).contains(&prob) {
+            return fallible!(FailedFunction, "prob must be in [1 / categories, 1]")
+        }
+        let prob_other = (1. - prob) / (categories - 1.);
+        Self::new_from_atoms(vec![
+            // the true category on the first dataset
+            ((prob / prob_other).ln(), prob),
+            // the true category on the second dataset
+            ((prob_other / prob).ln(), prob_other),
+            // any other category
+            (0., (categories - 2.) * prob_other),
+        ], discretization)
+    }
+
+    /// The PLD of the composition of the mechanisms described by `self` and `other`.
+    pub fn compose(&self, other: &Self) -> Fallible<Self> {
+        if self.discretization != other.discretization {
+            return fallible!(FailedFunction, "privacy loss distributions must share the same discretization")
+        }
+        if !self.probs.is_empty() && !other.probs.is_empty() {
+            grid_bounds(
+                (self.offset + other.offset) as f64,
+                (self.max_index() + other.max_index()) as f64)?;
+        }
+        Ok(PrivacyLossDistribution {
+            discretization: self.discretization,
+            offset: self.offset + other.offset,
+            probs: convolve(&self.probs, &other.probs),
+            infinity_mass: 1. - (1. - self.infinity_mass) * (1. - other.infinity_mass),
+        })
+    }
+
+    /// The PLD of `num_compositions` compositions of the mechanism described by `self`.
+    pub fn self_compose(&self, num_compositions: u32) -> Fallible<Self> {
+        let mut result = Self::new_identity(self.discretization)?;
+        let mut base = self.clone();
+        let mut remaining = num_compositions;
+        while remaining > 0 {
+            if remaining % 2 == 1 {
+                result = result.compose(&base)?;
+            }
+            remaining /= 2;
+            if remaining > 0 {
+                base = base.compose(&base)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// The smallest delta such that the mechanism is (`epsilon`, delta)-DP.
+    pub fn delta(&self, epsilon: f64) -> Fallible<f64> {
+        if epsilon.is_nan() {
+            return fallible!(InvalidDistance, "epsilon must not be NaN")
+        }
+        let delta = self.probs.iter().enumerate()
+            .map(|(i, &prob)| (self.loss(i), prob))
+            .filter(|&(loss, _)| loss > epsilon)
+            .map(|(loss, prob)| prob * -(epsilon - loss).exp_m1())
+            .sum::<f64>() + self.infinity_mass;
+        Ok(delta.min(1.))
+    }
+
+    /// The smallest non-negative epsilon such that the mechanism is (epsilon, `delta`)-DP, up to the precision of a binary search.
+    pub fn epsilon(&self, delta: f64) -> Fallible<f64> {
+        if delta.is_nan() || delta.is_sign_negative() {
+            return fallible!(InvalidDistance, "delta must be non-negative")
+        }
+        if self.infinity_mass > delta {
+            return Ok(f64::INFINITY)
+        }
+        let (mut lower, mut upper) = (0., self.loss(self.probs.len()).max(0.));
+        if self.delta(lower)? <= delta {
+            return Ok(lower)
+        }
+        // the upper bound is always private, so keep it as the result
+        for _ in 0..100 {
+            let mid = lower + (upper - lower) / 2.;
+            if mid <= lower || mid >= upper { break }
+            if self.delta(mid)? <= delta { upper = mid } else { lower = mid }
+        }
+        Ok(upper)
+    }
+
+    /// Whether the mechanism described by `self` is at least as private as the one described by `other`,
+    /// that is, whether its delta is no larger at every epsilon.
+    ///
+    /// Between neighboring grid points, the difference of two delta curves is monotone,
+    /// so it suffices to compare the curves on the grid.
+    pub fn is_dominated_by(&self, other: &Self) -> Fallible<bool> {
+        if self.discretization != other.discretization {
+            return fallible!(FailedFunction, "privacy loss distributions must share the same discretization")
+        }
+        let end = self.max_index().max(other.max_index()).max(0);
+        Ok(self.grid_deltas(end).into_iter()
+            .zip(other.grid_deltas(end))
+            .all(|(delta, other_delta)| delta <= other_delta))
+    }
+
+    /// Delta at each epsilon on the grid from zero through `end` grid points.
+    /// Beyond the largest finite loss, delta is the probability of an infinite loss.
+    fn grid_deltas(&self, end: i64) -> Vec<f64> {
+        let decay = (-self.discretization).exp();
+        // the mass of the losses above the current epsilon, and the same mass weighted by exp(epsilon - loss)
+        let (mut mass, mut weighted) = (0., 0.);
+        let mut deltas = vec![0.; end as usize + 1];
+        for k in (0..=end).rev() {
+            let prob = self.prob_at(k + 1);
+            mass += prob;
+            weighted = (weighted + prob) * decay;
+            deltas[k as usize] = (self.infinity_mass + mass - weighted).min(1.);
+        }
+        deltas
+    }
+
+    fn max_index(&self) -> i64 {
+        self.offset + self.probs.len() as i64 - 1
+    }
+
+    fn prob_at(&self, index: i64) -> f64 {
+        usize::try_from(index - self.offset).ok()
+            .and_then(|i| self.probs.get(i).cloned())
+            .unwrap_or(0.)
+    }
+
+    fn loss(&self, i: usize) -> f64 {
+        (self.offset + i as i64) as f64 * self.discretization
+    }
+}
+
+/// Construct a privacy relation from a map from input distance to the privacy loss distribution of a mechanism.
+/// The relation holds when the distribution is dominated by the output distance,
+/// and the forward map carries the distribution itself, so that combinators can compose it.
+pub fn make_pld_privacy_relation<MI: 'static + Metric>(
+    pld: impl Fn(&MI::Distance) -> Fallible<PrivacyLossDistribution> + 'static
+) -> PrivacyRelation<MI, PrivacyLossDivergence> {
+    let pld = Rc::new(pld);
+    PrivacyRelation::new_all(
+        enclose!(pld, move |d_in: &MI::Distance, d_out: &PrivacyLossDistribution| pld(d_in)?.is_dominated_by(d_out)),
+        Some(move |d_in: &MI::Distance| pld(d_in).map(Box::new)),
+        None::<fn(&_) -> _>)
+}
+
+pub(crate) fn check_discretization(discretization: f64) -> Fallible<()> {
+    if !discretization.is_finite() || discretization <= 0. {
+        return fallible!(FailedFunction, "discretization must be positive and finite")
+    }
+    Ok(())
+}
+
+/// Check that a grid spanning the indices `lower` through `upper` is small enough to allocate,
+/// and return its offset and number of points.
+fn grid_bounds(lower: f64, upper: f64) -> Fallible<(i64, usize)> {
+    if !(lower.abs() <= MAX_GRID_INDEX && upper.abs() <= MAX_GRID_INDEX) {
+        return fallible!(FailedFunction, "privacy loss is too large for the discretization")
+    }
+    let len = upper - lower + 1.;
+    if len > MAX_GRID_LENGTH as f64 {
+        return fallible!(FailedFunction, "privacy loss distribution would have {} grid points, but at most {} are allowed; increase the discretization", len, MAX_GRID_LENGTH)
+    }
+    Ok((lower as i64, len as usize))
+}
+
+fn check_scale(scale: f64, sensitivity: f64) -> Fallible<()> {
+    if scale.is_nan() || scale.is_sign_negative() {
+        return fallible!(FailedFunction, "scale must be non-negative")
+    }
+    if !sensitivity.is_finite() || sensitivity.is_sign_negative() {
+        return fallible!(InvalidDistance, "sensitivity must be non-negative and finite")
+    }
+    Ok(())
+}
+
+/// Convolve two probability mass functions.
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new()
+    }
+    let len = a.len() + b.len() - 1;
+    if a.len() * b.len() <= DIRECT_CONVOLUTION_LIMIT {
+        let mut result = vec![0.; len];
+        a.iter().enumerate().for_each(|(i, a_i)| b.iter().enumerate()
+            .for_each(|(j, b_j)| result[i + j] += a_i * b_j));
+        return result
+    }
+
+    let size = len.next_power_of_two();
+    let transform = |x: &[f64]| {
+        let mut x = x.iter().map(|v| Complex64::new(*v, 0.)).collect::<Vec<_>>();
+        x.resize(size, Complex64::new(0., 0.));
+        fft(&mut x, false);
+        x
+    };
+    let mut product = transform(a).into_iter().zip(transform(b))
+        .map(|(a_k, b_k)| a_k * b_k)
+        .collect::<Vec<_>>();
+    fft(&mut product, true);
+    // rounding error in the transform can leave tiny negative probabilities
+    product.into_iter().take(len).map(|v| (v.re / size as f64).max(0.)).collect()
+}
+
+/// In-place iterative radix-2 fast fourier transform. The length of `x` must be a power of two.
+/// The inverse transform is left unnormalized.
+fn fft(x: &mut [Complex64], inverse: bool) {
+    let n = x.len();
+    // bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j { x.swap(i, j) }
+    }
+
+    let sign = if inverse { 1. } else { -1. };
+    let mut len = 2;
+    while len <= n {
+        let angle = sign * 2. * PI / len as f64;
+        let root = Complex64::new(angle.cos(), angle.sin());
+        for start in (0..n).step_by(len) {
+            let mut w = Complex64::new(1., 0.);
+            for k in 0..len / 2 {
+                let u = x[start + k];
+                let v = x[start + k + len / 2] * w;
+                x[start + k] = u + v;
+                x[start + k + len / 2] = u - v;
+                w *= root;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // tight delta(epsilon) of the gaussian mechanism, from Balle and Wang (2018)
+    fn gaussian_delta(scale: f64, sensitivity: f64, epsilon: f64) -> f64 {
+        let normal_cdf = |z: f64| erfc(-z / SQRT_2) / 2.;
+        let (a, b) = (sensitivity / (2. * scale), epsilon * scale / sensitivity);
+        normal_cdf(a - b) - epsilon.exp() * normal_cdf(-a - b)
+    }
+
+    #[test]
+    fn test_laplace() -> Fallible<()> {
+        let pld = PrivacyLossDistribution::new_laplace(1., 1., 1e-4)?;
+        assert!((pld.delta(0.)? - (1. - (-0.5f64).exp())).abs() < 1e-3);
+        assert!(pld.delta(1.)? < 1e-4);
+        assert!(pld.delta(0.5)? > pld.delta(0.6)?);
+
+        // two laplace releases are (2, 0)-DP under basic composition
+        let composed = pld.self_compose(2)?;
+        assert!(composed.delta(2.)? < 1e-4);
+        assert!(composed.delta(1.5)? > 0.);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gaussian() -> Fallible<()> {
+        let pld = PrivacyLossDistribution::new_gaussian(1., 1., 1e-3)?;
+        let expected = gaussian_delta(1., 1., 1.);
+        let delta = pld.delta(1.)?;
+        assert!(delta >= expected && delta - expected < 1e-3);
+        assert!((pld.epsilon(expected)? - 1.).abs() < 1e-2);
+
+        // four compositions at scale 2 are equivalent to one release at scale 1
+        let composed = PrivacyLossDistribution::new_gaussian(2., 1., 1e-3)?.self_compose(4)?;
+        let delta = composed.delta(1.)?;
+        assert!(delta >= expected && delta - expected < 1e-2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_geometric() -> Fallible<()> {
+        let pld = PrivacyLossDistribution::new_geometric(1., 2, 1e-4)?;
+        assert!(pld.delta(2.)? < 1e-4);
+        assert!(pld.delta(1.)? > 0.);
+        Ok(())
+    }
+
+    #[test]
+    fn test_randomized_response() -> Fallible<()> {
+        let (prob, categories) = (0.5, 4);
+        let pld = PrivacyLossDistribution::new_randomized_response(prob, categories, 1e-4)?;
+        // the closed form of k-ary randomized response: delta(epsilon) = max(0, p - exp(epsilon) * (1 - p) / (k - 1))
+        let prob_other = (1. - prob) / (categories - 1) as f64;
+        for &epsilon in &[0., 0.5, 1., 3f64.ln(), 2.] {
+            let expected = (prob - epsilon.exp() * prob_other).max(0.);
+            let delta = pld.delta(epsilon)?;
+            assert!(delta >= expected && delta - expected < 1e-3);
+        }
+        assert!(PrivacyLossDistribution::new_randomized_response(0.1, 2, 1e-4).is_err());
+        assert!(PrivacyLossDistribution::new_randomized_response(0.75, 1, 1e-4).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid_length() -> Fallible<()> {
+        // the loss spans [-1e10, 1e10], which would need about 2e14 grid points
+        assert!(PrivacyLossDistribution::new_laplace(1e-10, 1., 1e-4).is_err());
+        assert!(PrivacyLossDistribution::new_from_atoms(vec![(-1e10, 0.5), (1e10, 0.5)], 1e-4).is_err());
+        assert!(PrivacyLossDistribution::new_from_atoms(vec![(1e300, 1.)], 1e-4).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_dominated_by() -> Fallible<()> {
+        let pld = PrivacyLossDistribution::new_gaussian(2., 1., 1e-3)?;
+        assert!(pld.is_dominated_by(&pld)?);
+        assert!(pld.is_dominated_by(&PrivacyLossDistribution::new_gaussian(1., 1., 1e-3)?)?);
+        assert!(!pld.self_compose(2)?.is_dominated_by(&pld)?);
+        assert!(PrivacyLossDistribution::new_identity(1e-3)?.is_dominated_by(&pld)?);
+        assert!(pld.is_dominated_by(&PrivacyLossDistribution::new_gaussian(2., 1., 1e-4)?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_convolve() {
+        let a = (0..300).map(|i| i as f64).collect::<Vec<_>>();
+        let b = (0..300).map(|i| (i % 7) as f64).collect::<Vec<_>>();
+        let mut direct = vec![0.; a.len() + b.len() - 1];
+        a.iter().enumerate().for_each(|(i, a_i)| b.iter().enumerate()
+            .for_each(|(j, b_j)| direct[i + j] += a_i * b_j));
+        let fast = convolve(&a, &b);
+        assert!(direct.iter().zip(fast.iter()).all(|(d, f)| (d - f).abs() < 1e-6 * d.max(1.)));
+    }
+}
@@ -3,7 +3,7 @@ use std::ops::{AddAssign, Neg, SubAssign, Sub};
 
 use ieee754::Ieee754;
 
-use num::{One, Zero, Bounded, clamp};
+use num::{One, Zero, Bounded, Signed, Integer, ToPrimitive, FromPrimitive, BigInt, BigUint, BigRational, clamp};
 #[cfg(feature="use-mpfr")]
 use rug::{Float, rand::{ThreadRandGen, ThreadRandState}};
 
@@ -127,6 +127,32 @@ impl SampleBernoulli for bool {
     }
 }
 
+pub trait SampleUniformInt: Sized {
+    /// Sample an integer uniformly from [0, `upper`).
+    ///
+    /// Random bytes are drawn until they encode an integer below the largest multiple of `upper`,
+    /// so that the result has no modulo bias.
+    fn sample_uniform_int_0_u(upper: Self) -> Fallible<Self>;
+}
+
+macro_rules! impl_sample_uniform_int {
+    ($($ty:ty),+) => {
+        $(impl SampleUniformInt for $ty {
+            fn sample_uniform_int_0_u(upper: Self) -> Fallible<Self> {
+                if upper == 0 { return fallible!(FailedFunction, "upper must be positive") }
+                let threshold = <$ty>::MAX - <$ty>::MAX % upper;
+                let mut buffer = [0u8; std::mem::size_of::<$ty>()];
+                loop {
+                    fill_bytes(&mut buffer)?;
+                    let sample = <$ty>::from_ne_bytes(buffer);
+                    if sample < threshold { return Ok(sample % upper) }
+                }
+            }
+        })+
+    }
+}
+impl_sample_uniform_int!(u32, u64, usize);
+
 pub trait SampleRademacher: Sized {
     fn sample_standard_rademacher() -> Fallible<Self>;
     fn sample_rademacher(prob: f64, constant_time: bool) -> Fallible<Self>;
@@ -385,6 +411,193 @@ impl<T: Clone + SampleGeometric + Sub<Output=T> + Bounded + Zero + One + TotalOr
 }
 
 
+impl SampleUniformInt for BigUint {
+    fn sample_uniform_int_0_u(upper: Self) -> Fallible<Self> {
+        if upper.is_zero() { return fallible!(FailedFunction, "upper must be positive") }
+        let excess_bits = upper.bits() % 8;
+        let mut buffer = vec![0u8; upper.to_bytes_le().len()];
+        loop {
+            fill_bytes(&mut buffer)?;
+            // discard the excess high bits, so that at least half of all draws are accepted
+            if excess_bits > 0 {
+                *buffer.last_mut().unwrap() &= (1u8 << excess_bits) - 1;
+            }
+            let sample = BigUint::from_bytes_le(&buffer);
+            if sample < upper { return Ok(sample) }
+        }
+    }
+}
+
+/// Sample a single bit that is 1 with exactly probability `prob`, a rational in [0, 1].
+fn sample_bernoulli_rational(prob: &BigRational) -> Fallible<bool> {
+    if prob.is_negative() || prob > &BigRational::one() {
+        return fallible!(FailedFunction, "probability is not within [0, 1]")
+    }
+    let numer = prob.numer().to_biguint().unwrap_or_else(BigUint::zero);
+    let denom = prob.denom().to_biguint().unwrap_or_else(BigUint::one);
+    Ok(BigUint::sample_uniform_int_0_u(denom)? < numer)
+}
+
+/// Sample a single bit that is 1 with exactly probability exp(-`gamma`), for a rational `gamma` >= 0.
+///
+/// Implements Algorithm 1 of [Canonne, Kamath and Steinke](https://arxiv.org/abs/2004.00010).
+/// Only Bernoulli trials with rational probabilities are used, so no floating-point approximation is made.
+pub(crate) fn sample_bernoulli_exp(gamma: &BigRational) -> Fallible<bool> {
+    if gamma.is_negative() {
+        return fallible!(FailedFunction, "gamma must be non-negative")
+    }
+    // exp(-gamma) = exp(-1)^floor(gamma) * exp(-fract(gamma))
+    let mut whole = gamma.to_integer();
+    let one = BigRational::one();
+    while whole.is_positive() {
+        if !sample_bernoulli_exp1(&one)? { return Ok(false) }
+        whole -= BigInt::one();
+    }
+    sample_bernoulli_exp1(&gamma.fract())
+}
+
+/// Sample a single bit that is 1 with exactly probability exp(-`gamma`), for a rational `gamma` in [0, 1].
+fn sample_bernoulli_exp1(gamma: &BigRational) -> Fallible<bool> {
+    let mut k = BigInt::one();
+    while sample_bernoulli_rational(&(gamma / &k))? {
+        k += BigInt::one();
+    }
+    Ok(k.is_odd())
+}
+
+/// Sample from the geometric distribution on {0, 1, 2, ...} with success probability 1 - exp(-1),
+/// by counting Bernoulli(exp(-1)) failures.
+fn sample_geometric_exp_neg_one() -> Fallible<BigInt> {
+    let mut count = BigInt::zero();
+    while sample_bernoulli_exp(&BigRational::one())? {
+        count += BigInt::one();
+    }
+    Ok(count)
+}
+
+/// Sample from the discrete laplace distribution with rational scale `scale` = t / s,
+/// where Pr[X = x] is proportional to exp(-|x| / scale).
+///
+/// Implements Algorithm 2 of [Canonne, Kamath and Steinke](https://arxiv.org/abs/2004.00010).
+fn sample_discrete_laplace_rational(scale: &BigRational) -> Fallible<BigInt> {
+    if !scale.is_positive() { return fallible!(FailedFunction, "scale must be positive") }
+    let (t, s) = (scale.numer(), scale.denom());
+    let t_unsigned = t.to_biguint().unwrap_or_else(BigUint::one);
+    loop {
+        let u = BigInt::from(BigUint::sample_uniform_int_0_u(t_unsigned.clone())?);
+        if !sample_bernoulli_exp(&BigRational::new(u.clone(), t.clone()))? { continue }
+
+        let x = (u + sample_geometric_exp_neg_one()? * t).div_floor(s);
+        let negative = bool::sample_standard_bernoulli()?;
+        // reject negative zero, so that zero is not double-counted
+        if negative && x.is_zero() { continue }
+        return Ok(if negative { -x } else { x })
+    }
+}
+
+/// Add `noise` to `shift`, saturating at the bounds of the data type.
+fn add_noise_saturating<T: Bounded + ToPrimitive + FromPrimitive>(shift: T, noise: BigInt) -> Fallible<T> {
+    // unsigned shifts may exceed i128::MAX
+    let shift = shift.to_i128().map(BigInt::from)
+        .or_else(|| shift.to_u128().map(BigInt::from))
+        .ok_or_else(|| err!(FailedFunction, "shift must be an integer"))?;
+    let noised = shift + noise;
+
+    Ok(noised.to_i128().and_then(T::from_i128)
+        .or_else(|| noised.to_u128().and_then(T::from_u128))
+        .unwrap_or_else(|| if noised.is_negative() { T::min_value() } else { T::max_value() }))
+}
+
+pub trait SampleDiscreteLaplace: Sized {
+
+    /// Sample from the discrete laplace distribution centered at `shift`,
+    /// where Pr[X = x] is proportional to exp(-|x - shift| / scale).
+    ///
+    /// Unlike [`SampleTwoSidedGeometric`], the scale is rational and only Bernoulli(exp(-gamma)) trials
+    /// with rational gamma are used, so no floating-point approximation affects the output distribution.
+    /// Execution time is not protected against timing attacks.
+    ///
+    /// Samples that overflow the data type saturate at the bounds of the data type.
+    ///
+    /// # Arguments
+    /// * `shift` - Parameter to shift the output by
+    /// * `scale` - Rational scale of the distribution. Must be non-negative.
+    ///
+    /// # Return
+    /// A draw from the discrete laplace distribution defined above.
+    ///
+    /// # Example
+    /// ```
+    /// use num::{BigInt, BigRational};
+    /// use opendp::samplers::SampleDiscreteLaplace;
+    /// // scale = 5/2
+    /// let scale = BigRational::new(BigInt::from(5), BigInt::from(2));
+    /// let laplace = i32::sample_discrete_laplace(0, &scale);
+    /// # use opendp::error::ExplainUnwrap;
+    /// # laplace.unwrap_test();
+    /// ```
+    fn sample_discrete_laplace(shift: Self, scale: &BigRational) -> Fallible<Self>;
+}
+
+impl<T: Bounded + ToPrimitive + FromPrimitive> SampleDiscreteLaplace for T {
+    fn sample_discrete_laplace(shift: Self, scale: &BigRational) -> Fallible<Self> {
+        if scale.is_negative() { return fallible!(FailedFunction, "scale must not be negative") }
+        if scale.is_zero() { return Ok(shift) }
+        add_noise_saturating(shift, sample_discrete_laplace_rational(scale)?)
+    }
+}
+
+pub trait SampleDiscreteGaussian: Sized {
+
+    /// Sample from the discrete gaussian distribution centered at `shift`,
+    /// where Pr[X = x] is proportional to exp(-(x - shift)^2 / (2 * scale^2)).
+    ///
+    /// Implements Algorithm 3 of [Canonne, Kamath and Steinke](https://arxiv.org/abs/2004.00010).
+    /// `scale` is converted exactly to a rational, and all sampling is done with rational Bernoulli trials,
+    /// so the output distribution is exact. Execution time is not protected against timing attacks.
+    ///
+    /// Samples that overflow the data type saturate at the bounds of the data type.
+    ///
+    /// # Arguments
+    /// * `shift` - Parameter to shift the output by
+    /// * `scale` - Standard deviation of the underlying continuous gaussian
+    ///
+    /// # Return
+    /// A draw from the discrete gaussian distribution defined above.
+    ///
+    /// # Example
+    /// ```
+    /// use opendp::samplers::SampleDiscreteGaussian;
+    /// let gauss = i32::sample_discrete_gaussian(0, 2.5);
+    /// # use opendp::error::ExplainUnwrap;
+    /// # gauss.unwrap_test();
+    /// ```
+    fn sample_discrete_gaussian(shift: Self, scale: f64) -> Fallible<Self>;
+}
+
+impl<T: Bounded + ToPrimitive + FromPrimitive> SampleDiscreteGaussian for T {
+    fn sample_discrete_gaussian(shift: Self, scale: f64) -> Fallible<Self> {
+        let scale = BigRational::from_float(scale)
+            .ok_or_else(|| err!(FailedFunction, "scale must be finite"))?;
+        if scale.is_negative() { return fallible!(FailedFunction, "scale must not be negative") }
+        if scale.is_zero() { return Ok(shift) }
+
+        let sigma_sq = &scale * &scale;
+        let t = scale.floor() + BigRational::one();
+        let _2 = BigRational::from_integer(BigInt::from(2));
+
+        let noise = loop {
+            let y = sample_discrete_laplace_rational(&t)?;
+            // gamma = (|y| - sigma^2 / t)^2 / (2 * sigma^2)
+            let diff = BigRational::from_integer(y.abs()) - &sigma_sq / &t;
+            let gamma = &diff * &diff / (&_2 * &sigma_sq);
+            if sample_bernoulli_exp(&gamma)? { break y }
+        };
+        add_noise_saturating(shift, noise)
+    }
+}
+
+
 pub trait SampleLaplace: SampleRademacher + Sized {
     fn sample_laplace(shift: Self, scale: Self, constant_time: bool) -> Fallible<Self>;
 }
@@ -523,4 +736,42 @@ impl SampleGaussian for f32 {
         let uniform_sample = f64::sample_standard_uniform(constant_time)?;
         Ok(shift + scale * std::f32::consts::SQRT_2 * (erf::erfc_inv(2.0 * uniform_sample) as f32))
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_discrete_laplace_variance() -> Fallible<()> {
+        let scale = BigRational::new(BigInt::from(5), BigInt::from(2));
+        let samples = (0..10_000)
+            .map(|_| i64::sample_discrete_laplace(0, &scale))
+            .collect::<Fallible<Vec<_>>>()?;
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<i64>() as f64 / n;
+        let variance = samples.iter().map(|&v| (v as f64 - mean).powi(2)).sum::<f64>() / n;
+
+        // the variance is 2 * exp(-1 / b) / (1 - exp(-1 / b))^2, about 12.34 when b = 5/2.
+        // The standard deviation of the sample variance is about 0.28, so the tolerance is over 5 standard deviations
+        let decay = (-1. / 2.5f64).exp();
+        let expected = 2. * decay / (1. - decay).powi(2);
+        assert!(mean.abs() < 0.2, "mean: {}", mean);
+        assert!((variance - expected).abs() < 1.5, "variance: {}, expected: {}", variance, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sample_discrete_laplace_large_shift() -> Fallible<()> {
+        let scale = BigRational::new(BigInt::from(5), BigInt::from(2));
+        // shifts above i128::MAX are noised, not rejected
+        let shift = i128::MAX as u128 + 1000;
+        let sample = u128::sample_discrete_laplace(shift, &scale)?;
+        assert!(sample.max(shift) - sample.min(shift) < 1000);
+        // and the output saturates at the bounds of the data type
+        let sample = u128::sample_discrete_laplace(u128::MAX, &scale)?;
+        assert!(sample > u128::MAX - 1000);
+        Ok(())
+    }
+}
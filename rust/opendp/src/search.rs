@@ -0,0 +1,118 @@
+//! Binary search over distances and parameters.
+//!
+//! Relations are monotone in their distances, and the relations of most constructors are monotone in their parameters,
+//! so the tightest distance or parameter can be found by binary search.
+
+use crate::core::{Domain, Measure, Measurement, Metric};
+use crate::error::Fallible;
+
+/// Non-negative values that can be binary searched.
+pub trait Bisect: 'static + Sized {
+    /// The smallest and largest values to search between.
+    fn bisect_bounds() -> (Self, Self);
+    /// A value strictly between `lower` and `upper`, if there is one.
+    fn bisect_midpoint(lower: &Self, upper: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_bisect_int {
+    ($($ty:ty),+) => ($(impl Bisect for $ty {
+        fn bisect_bounds() -> (Self, Self) { (0, <$ty>::MAX) }
+        fn bisect_midpoint(lower: &Self, upper: &Self) -> Option<Self> {
+            let mid = lower + (upper - lower) / 2;
+            (mid != *lower).then(|| mid)
+        }
+    })+)
+}
+impl_bisect_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_bisect_float {
+    ($($ty:ty),+) => ($(impl Bisect for $ty {
+        fn bisect_bounds() -> (Self, Self) { (0., <$ty>::MAX) }
+        // the bits of non-negative floats are ordered like the floats, so bisect the bits
+        fn bisect_midpoint(lower: &Self, upper: &Self) -> Option<Self> {
+            let (lower, upper) = (lower.to_bits(), upper.to_bits());
+            let mid = lower + (upper - lower) / 2;
+            (mid != lower).then(|| <$ty>::from_bits(mid))
+        }
+    })+)
+}
+impl_bisect_float!(f32, f64);
+
+/// Finds the smallest value for which `predicate` holds, assuming that `predicate` is monotone.
+/// Returns None if `predicate` does not hold for any value.
+pub fn bisect_min<T: Bisect>(predicate: impl Fn(&T) -> bool) -> Option<T> {
+    let (mut lower, mut upper) = T::bisect_bounds();
+    if predicate(&lower) { return Some(lower) }
+    if !predicate(&upper) { return None }
+    while let Some(mid) = T::bisect_midpoint(&lower, &upper) {
+        if predicate(&mid) { upper = mid } else { lower = mid }
+    }
+    Some(upper)
+}
+
+/// Finds the smallest scale for which the Measurement made by `make_measurement` satisfies its privacy relation at (`d_in`, `d_out`).
+///
+/// `make_measurement` may construct a whole chain, like `make_bounded_sum(bounds)? >> make_base_laplace(scale)?`,
+/// so long as the chain becomes more private as the scale grows.
+/// Scales where construction or the privacy relation fail are treated as not private.
+///
+/// # Example
+/// ```
+/// use opendp::dom::AllDomain;
+/// use opendp::meas::make_base_laplace;
+/// use opendp::search::find_scale;
+/// # use opendp::error::Fallible;
+/// # fn main() -> Fallible<()> {
+/// let scale = find_scale(make_base_laplace::<AllDomain<f64>>, &1., &0.5)?;
+/// assert_eq!(scale, 2.);
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_scale<Q, DI, DO, MI, MO>(
+    make_measurement: impl Fn(Q) -> Fallible<Measurement<DI, DO, MI, MO>>,
+    d_in: &MI::Distance,
+    d_out: &MO::Distance,
+) -> Fallible<Q>
+    where Q: Bisect + Clone,
+          DI: Domain, DO: Domain,
+          MI: Metric, MO: Measure {
+    bisect_min(|scale: &Q| make_measurement(scale.clone())
+        .and_then(|measurement| measurement.check(d_in, d_out))
+        .unwrap_or(false))
+        .ok_or_else(|| err!(FailedRelation, "no scale satisfies the privacy relation at the given d_in and d_out"))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::dom::AllDomain;
+    use crate::meas::{make_base_gaussian, make_base_laplace};
+    use crate::trans::{make_bounded_sum, make_clamp};
+
+    use super::*;
+
+    #[test]
+    fn test_bisect_min() {
+        assert_eq!(bisect_min(|v: &u32| *v > 5), Some(6));
+        assert_eq!(bisect_min(|v: &f64| *v >= 5.), Some(5.));
+        assert_eq!(bisect_min(|_: &u8| false), None);
+    }
+
+    #[test]
+    fn test_find_scale() -> Fallible<()> {
+        assert_eq!(find_scale(make_base_laplace::<AllDomain<f64>>, &1., &1.)?, 1.);
+
+        // the sum has a stability constant of 10, so the scale must be 10 times larger
+        let scale = find_scale(|scale| make_clamp((0., 10.))? >> make_bounded_sum((0., 10.))? >> make_base_laplace(scale)?, &1, &1.)?;
+        assert_eq!(scale, 10.);
+
+        let scale = find_scale(make_base_gaussian::<AllDomain<f64>>, &1., &(1., 1e-6))?;
+        let measurement = make_base_gaussian::<AllDomain<f64>>(scale)?;
+        assert!(measurement.check(&1., &(1., 1e-6))?);
+        assert!(!make_base_gaussian::<AllDomain<f64>>(scale * 0.999)?.check(&1., &(1., 1e-6))?);
+
+        // no finite scale gives zero privacy loss
+        assert!(find_scale(make_base_laplace::<AllDomain<f64>>, &1., &0.).is_err());
+        Ok(())
+    }
+}
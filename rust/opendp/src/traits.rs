@@ -59,6 +59,33 @@ impl<T> MeasureDistance for T where T: PartialOrd + for<'a> FallibleSub<&'a Self
 pub trait MetricDistance: PartialOrd {}
 impl<T> MetricDistance for T where T: PartialOrd {}
 
+/// A distance that is ordered component by component, like the (epsilon, delta) of approximate DP.
+/// Unlike the lexicographic `PartialOrd` of a tuple, a distance is only within a bound if every component is.
+pub trait ProductOrd {
+    fn is_within(&self, bound: &Self) -> bool;
+}
+
+macro_rules! impl_product_ord {
+    ($($ty:ty),+) => ($(
+        impl ProductOrd for $ty {
+            fn is_within(&self, bound: &Self) -> bool { self <= bound }
+        }
+    )+)
+}
+impl_product_ord!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl<T0: ProductOrd, T1: ProductOrd> ProductOrd for (T0, T1) {
+    fn is_within(&self, bound: &Self) -> bool {
+        self.0.is_within(&bound.0) && self.1.is_within(&bound.1)
+    }
+}
+
+impl<T: ProductOrd> ProductOrd for Vec<T> {
+    fn is_within(&self, bound: &Self) -> bool {
+        self.len() == bound.len() && self.iter().zip(bound.iter()).all(|(d, bound)| d.is_within(bound))
+    }
+}
+
 
 pub trait Abs { fn abs(self) -> Self; }
 macro_rules! impl_abs_method {
@@ -372,6 +399,12 @@ impl<T: CheckNull> CheckNull for Option<T> {
         } else {true}
     }
 }
+impl<T0: CheckNull, T1: CheckNull> CheckNull for (T0, T1) {
+    #[inline]
+    fn is_null(&self) -> bool {
+        self.0.is_null() || self.1.is_null()
+    }
+}
 macro_rules! impl_check_null_for_float {
     ($($ty:ty),+) => {
         $(impl CheckNull for $ty {
@@ -23,6 +23,11 @@ pub mod count;
 #[cfg(feature="contrib")]
 pub use crate::trans::count::*;
 
+#[cfg(feature="contrib")]
+pub mod partition;
+#[cfg(feature="contrib")]
+pub use crate::trans::partition::*;
+
 #[cfg(feature="contrib")]
 pub mod mean;
 #[cfg(feature="contrib")]
@@ -53,3 +58,8 @@ pub mod resize;
 #[cfg(feature="contrib")]
 pub use crate::trans::resize::*;
 
+#[cfg(feature="contrib")]
+pub mod subsample;
+#[cfg(feature="contrib")]
+pub use crate::trans::subsample::*;
+
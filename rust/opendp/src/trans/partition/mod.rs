@@ -0,0 +1,71 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::core::{Function, StabilityRelation, Transformation};
+use crate::dist::{IntDistance, PartitionDistance, SymmetricDistance};
+use crate::dom::{AllDomain, VectorDomain};
+use crate::error::*;
+use crate::traits::{CheckNull, ExactIntCast};
+
+/// Make a Transformation that partitions a dataset of (key, value) records into one dataset of values per key in `partition_keys`.
+/// Records whose key is not in `partition_keys` are discarded.
+///
+/// Adding or removing d_in records changes at most d_in partitions, each by at most d_in records.
+pub fn make_partition_by<TK, TV>(
+    partition_keys: Vec<TK>
+) -> Fallible<Transformation<VectorDomain<AllDomain<(TK, TV)>>, VectorDomain<VectorDomain<AllDomain<TV>>>, SymmetricDistance, PartitionDistance>>
+    where TK: 'static + Eq + Hash + Clone + CheckNull,
+          TV: 'static + Clone + CheckNull {
+    let mut uniques = HashSet::new();
+    if partition_keys.iter().any(move |x| !uniques.insert(x)) {
+        return fallible!(MakeTransformation, "partition_keys must be distinct")
+    }
+    let num_partitions = IntDistance::exact_int_cast(partition_keys.len())?;
+    let indexes = partition_keys.into_iter().enumerate()
+        .map(|(i, key)| (key, i)).collect::<HashMap<TK, usize>>();
+
+    Ok(Transformation::new(
+        VectorDomain::new_all(),
+        VectorDomain::new(VectorDomain::new_all()),
+        Function::new(move |data: &Vec<(TK, TV)>| {
+            let mut partitions = vec![Vec::new(); indexes.len()];
+            data.iter().for_each(|(key, value)| if let Some(&i) = indexes.get(key) {
+                partitions[i].push(value.clone())
+            });
+            partitions
+        }),
+        SymmetricDistance,
+        PartitionDistance,
+        StabilityRelation::new_all(
+            // relation
+            move |&d_in: &IntDistance, &(d_partition, num_changed): &(IntDistance, IntDistance)|
+                Ok(d_partition >= d_in && num_changed >= d_in.min(num_partitions)),
+            // forward map
+            Some(move |&d_in: &IntDistance| Ok(Box::new((d_in, d_in.min(num_partitions))))),
+            // backward map
+            Some(move |&(d_partition, num_changed): &(IntDistance, IntDistance)|
+                Ok(Box::new(if num_changed >= num_partitions { d_partition } else { d_partition.min(num_changed) }))),
+        )))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_partition_by() -> Fallible<()> {
+        let transformation = make_partition_by::<char, i32>(vec!['A', 'B', 'C'])?;
+        let arg = vec![('A', 1), ('C', 2), ('A', 3), ('D', 4)];
+        let ret = transformation.invoke(&arg)?;
+        assert_eq!(ret, vec![vec![1, 3], vec![], vec![2]]);
+
+        assert!(transformation.check(&1, &(1, 1))?);
+        assert!(!transformation.check(&2, &(2, 1))?);
+        // no more than all of the partitions can change
+        assert!(transformation.check(&5, &(5, 3))?);
+
+        assert!(make_partition_by::<char, i32>(vec!['A', 'A']).is_err());
+        Ok(())
+    }
+}
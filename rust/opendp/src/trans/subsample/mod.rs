@@ -0,0 +1,109 @@
+use crate::core::{Domain, Function, StabilityRelation, Transformation};
+use crate::dist::{IntDistance, SymmetricDistance};
+use crate::dom::{SizedDomain, VectorDomain};
+use crate::error::Fallible;
+use crate::samplers::{SampleBernoulli, SampleUniformInt};
+
+/// Make a Transformation that keeps each record independently with probability `rate` (Poisson sampling).
+///
+/// Adding or removing d_in records adds or removes at most d_in records from the sample.
+/// Use `make_poisson_amplification` to account for the privacy amplification of a downstream measurement.
+pub fn make_subsample_poisson<DA>(
+    atom_domain: DA, rate: f64,
+) -> Fallible<Transformation<VectorDomain<DA>, VectorDomain<DA>, SymmetricDistance, SymmetricDistance>>
+    where DA: 'static + Clone + Domain,
+          DA::Carrier: 'static + Clone {
+    if rate.is_nan() || rate <= 0. || rate > 1. {
+        return fallible!(MakeTransformation, "rate must be in (0, 1]")
+    }
+    Ok(Transformation::new(
+        VectorDomain::new(atom_domain.clone()),
+        VectorDomain::new(atom_domain),
+        Function::new_fallible(move |arg: &Vec<DA::Carrier>| {
+            let mut sample = Vec::new();
+            for record in arg {
+                if bool::sample_bernoulli(rate, false)? {
+                    sample.push(record.clone())
+                }
+            }
+            Ok(sample)
+        }),
+        SymmetricDistance,
+        SymmetricDistance,
+        StabilityRelation::new_from_constant(1)))
+}
+
+/// Make a Transformation that samples `sample_size` records uniformly at random, without replacement,
+/// from a dataset of known size.
+///
+/// Neighboring datasets of the same size differ by d_in / 2 substitutions, each of which changes at most one sampled record.
+/// Use `make_without_replacement_amplification` to account for the privacy amplification of a downstream measurement.
+pub fn make_subsample_without_replacement<DA>(
+    size: usize, sample_size: usize, atom_domain: DA,
+) -> Fallible<Transformation<SizedDomain<VectorDomain<DA>>, SizedDomain<VectorDomain<DA>>, SymmetricDistance, SymmetricDistance>>
+    where DA: 'static + Clone + Domain,
+          DA::Carrier: 'static + Clone {
+    if sample_size == 0 { return fallible!(MakeTransformation, "sample_size must be greater than zero") }
+    if sample_size > size { return fallible!(MakeTransformation, "sample_size must not exceed size") }
+
+    Ok(Transformation::new(
+        SizedDomain::new(VectorDomain::new(atom_domain.clone()), size),
+        SizedDomain::new(VectorDomain::new(atom_domain), sample_size),
+        Function::new_fallible(move |arg: &Vec<DA::Carrier>| {
+            if arg.len() != size {
+                return fallible!(FailedFunction, "the dataset must have {} records", size)
+            }
+            // partial Fisher-Yates shuffle of the indexes; the first sample_size indexes are the sample
+            let mut indexes = (0..size).collect::<Vec<usize>>();
+            for i in 0..sample_size {
+                let j = i + usize::sample_uniform_int_0_u(size - i)?;
+                indexes.swap(i, j);
+            }
+            Ok(indexes[..sample_size].iter().map(|&i| arg[i].clone()).collect())
+        }),
+        SymmetricDistance,
+        SymmetricDistance,
+        StabilityRelation::new_all(
+            |d_in: &IntDistance, d_out: &IntDistance| Ok(d_out >= d_in),
+            Some(|d_in: &IntDistance| Ok(Box::new(*d_in))),
+            Some(|d_out: &IntDistance| Ok(Box::new(*d_out))))))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::dom::AllDomain;
+
+    use super::*;
+
+    #[test]
+    fn test_make_subsample_poisson() -> Fallible<()> {
+        let transformation = make_subsample_poisson(AllDomain::new(), 0.5)?;
+        let arg = (0..100).collect::<Vec<i32>>();
+        let ret = transformation.invoke(&arg)?;
+        assert!(ret.iter().all(|v| arg.contains(v)));
+        assert_eq!(make_subsample_poisson(AllDomain::new(), 1.)?.invoke(&arg)?, arg);
+
+        assert!(transformation.check(&1, &1)?);
+        assert!(!transformation.check(&2, &1)?);
+        assert!(make_subsample_poisson::<AllDomain<i32>>(AllDomain::new(), 0.).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_subsample_without_replacement() -> Fallible<()> {
+        let transformation = make_subsample_without_replacement(10, 4, AllDomain::new())?;
+        let arg = (0..10).collect::<Vec<i32>>();
+        let mut ret = transformation.invoke(&arg)?;
+        ret.sort_unstable();
+        ret.dedup();
+        assert_eq!(ret.len(), 4);
+        assert!(ret.iter().all(|v| arg.contains(v)));
+        assert!(transformation.invoke(&vec![1, 2, 3]).is_err());
+
+        assert!(transformation.check(&2, &2)?);
+        assert!(!transformation.check(&2, &1)?);
+        assert!(make_subsample_without_replacement::<AllDomain<i32>>(3, 4, AllDomain::new()).is_err());
+        Ok(())
+    }
+}